@@ -0,0 +1,127 @@
+use crate::entities::items::ItemKind;
+use crate::events::{EventBus, EventListener, GameEvent};
+use crate::save::{parse_list, SaveFile};
+use crate::tile_types::TileType;
+
+/// What the achievement is called, how much the event counts towards it
+/// and how much it takes to earn it.
+struct Achievement {
+    name: &'static str,
+    counts: fn(&GameEvent) -> u64,
+    goal: u64,
+}
+
+/// All the achievements there are to earn.
+const ACHIEVEMENTS: [Achievement; 6] = [
+    Achievement {
+        name: "First blood",
+        counts: |event| match event {
+            GameEvent::EntityDied { tile, .. } if *tile != TileType::Pengu => 1,
+            _ => 0,
+        },
+        goal: 1,
+    },
+    Achievement {
+        name: "Slime slayer",
+        counts: |event| match event {
+            GameEvent::EntityDied {
+                tile: TileType::Slime,
+                ..
+            } => 1,
+            _ => 0,
+        },
+        goal: 10,
+    },
+    Achievement {
+        name: "Wanderer",
+        counts: |event| match event {
+            GameEvent::EntityMoved {
+                tile: TileType::Pengu,
+                ..
+            } => 1,
+            _ => 0,
+        },
+        goal: 1000,
+    },
+    Achievement {
+        name: "Explorer",
+        counts: |event| match event {
+            GameEvent::ChunkLoaded {
+                first_visit: true, ..
+            } => 1,
+            _ => 0,
+        },
+        goal: 25,
+    },
+    Achievement {
+        name: "Treasure hunter",
+        counts: |event| match event {
+            GameEvent::ItemPickedUp(item) if item.kind == ItemKind::Coin => u64::from(item.count),
+            _ => 0,
+        },
+        goal: 100,
+    },
+    Achievement {
+        name: "Survivor",
+        counts: |event| match event {
+            GameEvent::TurnEnded(_) => 1,
+            _ => 0,
+        },
+        goal: 5000,
+    },
+];
+
+/// How far the player got with every achievement.
+#[derive(Debug)]
+pub struct Achievements {
+    progress: [u64; ACHIEVEMENTS.len()],
+}
+
+impl Default for Achievements {
+    fn default() -> Self {
+        Self {
+            progress: [0; ACHIEVEMENTS.len()],
+        }
+    }
+}
+
+impl Achievements {
+    /// The names of the earned achievements.
+    pub fn unlocked(&self) -> impl Iterator<Item = &'static str> + '_ {
+        ACHIEVEMENTS
+            .iter()
+            .zip(self.progress)
+            .filter(|(achievement, progress)| *progress >= achievement.goal)
+            .map(|(achievement, _)| achievement.name)
+    }
+
+    pub fn save(&self, file: &mut SaveFile) {
+        let progress: Vec<String> = self.progress.iter().map(u64::to_string).collect();
+        file.push("achievements", progress.join(","));
+    }
+
+    /// Restores the progress written by `save`, returns None if it's missing or broken.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let mut achievements = Self::default();
+        let saved = file.get("achievements").and_then(parse_list::<u64>)?;
+        if saved.len() != achievements.progress.len() {
+            return None;
+        }
+        for (progress, saved) in achievements.progress.iter_mut().zip(saved) {
+            *progress = saved;
+        }
+        Some(achievements)
+    }
+}
+
+impl EventListener for Achievements {
+    fn on_event(&mut self, event: &GameEvent, bus: &mut EventBus) {
+        for (achievement, progress) in ACHIEVEMENTS.iter().zip(self.progress.iter_mut()) {
+            let was_unlocked = *progress >= achievement.goal;
+            *progress += (achievement.counts)(event);
+            if !was_unlocked && *progress >= achievement.goal {
+                bus.emit(GameEvent::AchievementUnlocked(achievement.name));
+            }
+        }
+    }
+}
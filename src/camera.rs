@@ -1,69 +1,113 @@
 use macroquad::prelude::*;
 
+use crate::controls::{Command, KeyBindings};
+
+/// The closest and the furthest the camera can zoom, in the screen pixels per tile.
+const MIN_TILE_PIXELS: f32 = 8.;
+const MAX_TILE_PIXELS: f32 = 160.;
+/// How much one notch of the mouse wheel zooms.
+const WHEEL_ZOOM: f32 = 1.25;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Camera {
     target: Vec2,
-    zoom: Vec2,
+    /// How many screen pixels a tile takes. It's a whole number
+    /// whenever the zoom isn't changing, so the pixel art stays crisp.
+    tile_pixels: f32,
 }
 
 impl Camera {
-    pub const fn get(&self) -> (Vec2, Vec2) {
-        (self.target, self.zoom)
+    /// The target and the zoom for `Camera2D`, the zoom fits the current window size.
+    pub fn get(&self) -> (Vec2, Vec2) {
+        let zoom = self.tile_pixels * 2. / screen_width();
+        (
+            self.target,
+            vec2(zoom, zoom * screen_width() / screen_height()),
+        )
     }
 
     pub fn set_target(&mut self, new_target: Vec2) {
         self.target = Vec2::new(new_target.x - 0.5, new_target.y + 0.5);
     }
 
+    /// Moves the camera a part of the way to the position, so it glides there
+    /// over a few frames. Returns whether the camera has arrived.
+    pub fn glide_to(&mut self, position: Vec2, damping: f32) -> bool {
+        let goal = Vec2::new(position.x - 0.5, position.y + 0.5);
+        let amount = 1. - (-damping * get_frame_time()).exp();
+        self.target += (goal - self.target) * amount;
+        if self.target.distance(goal) < 0.05 {
+            self.target = goal;
+            return true;
+        }
+        false
+    }
+
+    /// Eases the camera after the player. The player can move inside
+    /// the deadzone, a circle of `deadzone` tiles, without moving the camera.
+    pub fn follow(&mut self, position: Vec2, damping: f32, deadzone: f32) {
+        let goal = Vec2::new(position.x - 0.5, position.y + 0.5);
+        let offset = goal - self.target;
+        if offset.length() <= deadzone {
+            return;
+        }
+        let edge = goal - offset.clamp_length_max(deadzone);
+        let amount = 1. - (-damping * get_frame_time()).exp();
+        self.target += (edge - self.target) * amount;
+    }
+
     /// Get and handle the input related to the camera.
-    pub fn scroll(&mut self, _scroll_speed: f32, zoom_speed: f32) {
-        /*
-        // Move the camera:
-        // UP
-        if is_key_down(KeyCode::Comma) {
-            self.target
-                .set_y(self.target.y() + scroll_speed / self.zoom.x())
+    pub fn scroll(&mut self, scroll_speed: f32, zoom_speed: f32, bindings: &KeyBindings) {
+        let (_, zoom) = self.get();
+        // Move the camera, it stays where it was moved while it's detached from the player.
+        if bindings.down(Command::PanNorth) {
+            self.target.y += scroll_speed / zoom.x;
         }
-        // DOWN
-        if is_key_down(KeyCode::O) {
-            self.target
-                .set_y(self.target.y() - scroll_speed / self.zoom.x())
+        if bindings.down(Command::PanSouth) {
+            self.target.y -= scroll_speed / zoom.x;
         }
-        // LEFT
-        if is_key_down(KeyCode::A) {
-            self.target
-                .set_x(self.target.x() - scroll_speed / self.zoom.x())
+        if bindings.down(Command::PanWest) {
+            self.target.x -= scroll_speed / zoom.x;
         }
-        // RIGHT
-        if is_key_down(KeyCode::E) {
-            self.target
-                .set_x(self.target.x() + scroll_speed / self.zoom.x())
+        if bindings.down(Command::PanEast) {
+            self.target.x += scroll_speed / zoom.x;
         }
-        */
 
         // Change the camera zoom:
         // Further
-        if is_key_down(KeyCode::Apostrophe) {
-            self.zoom.x *= zoom_speed;
-            self.zoom.y *= zoom_speed;
+        let zoom_out = bindings.down(Command::ZoomOut);
+        if zoom_out {
+            self.tile_pixels *= zoom_speed;
         }
         // Closer
-        if is_key_down(KeyCode::Period) {
-            self.zoom.x /= zoom_speed;
-            self.zoom.y /= zoom_speed;
+        let zoom_in = bindings.down(Command::ZoomIn);
+        if zoom_in {
+            self.tile_pixels /= zoom_speed;
+        }
+        // The mouse wheel zooms in steps, keeping the tile under the cursor in place.
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0. {
+            let anchor = mouse_position_relative_to(self);
+            self.tile_pixels *= WHEEL_ZOOM.powf(wheel.signum());
+            self.tile_pixels = self
+                .tile_pixels
+                .round()
+                .clamp(MIN_TILE_PIXELS, MAX_TILE_PIXELS);
+            self.target += anchor - mouse_position_relative_to(self);
+        }
+        self.tile_pixels = self.tile_pixels.clamp(MIN_TILE_PIXELS, MAX_TILE_PIXELS);
+        // Snap to a whole number of pixels once the zoom keys are let go.
+        if !zoom_in && !zoom_out {
+            self.tile_pixels = self.tile_pixels.round();
         }
     }
 }
 
 impl Default for Camera {
     fn default() -> Self {
-        let starting_zoom = 0.10;
         Self {
             target: vec2(0.0, 0.0),
-            zoom: vec2(
-                starting_zoom,
-                starting_zoom * screen_width() / screen_height(),
-            ),
+            tile_pixels: 40.,
         }
     }
 }
@@ -73,14 +117,20 @@ pub fn mouse_position_relative_to(camera: &Camera) -> Vec2 {
     // Takes the mouse coordinates on window and translates that
     // to game world coordinates.
     let mouse = mouse_position();
+    let (target, zoom) = camera.get();
     let center = Vec2::new(
-        ((mouse.0 - screen_width() / 2.0) / (screen_width() / 2.0) / camera.zoom.x)
-            + camera.target.x,
+        ((mouse.0 - screen_width() / 2.0) / (screen_width() / 2.0) / zoom.x) + target.x,
         ((-mouse.1 + screen_height() / 2.0)
             / (screen_height() / 2.0)
-            / camera.zoom.x
+            / zoom.x
             / (screen_width() / screen_height()))
-            + camera.target.y,
+            + target.y,
     );
     Vec2::new(center.x, center.y)
 }
+
+/// The tile under the position in the game world.
+/// The tiles are drawn to the left of their position.
+pub fn tile_at(position: Vec2) -> (i32, i32) {
+    (position.x.floor() as i32 + 1, position.y.floor() as i32)
+}
@@ -0,0 +1,145 @@
+use crate::graphics::lighting::LightColor;
+
+/// How many turns one in-game day takes.
+pub const TURNS_PER_DAY: u64 = 720;
+/// The game starts at eight in the morning.
+const START_TURN: u64 = TURNS_PER_DAY * 8 / 24;
+
+/// How far the player sees in the darkest night and in the brightest day.
+const NIGHT_VISION: f32 = 8.;
+const DAY_VISION: f32 = 16.;
+
+const NIGHT_AMBIENT: LightColor = LightColor::new(0.03, 0.04, 0.1);
+const DAY_AMBIENT: LightColor = LightColor::new(0.85, 0.85, 0.8);
+/// The reddish tint of the sunrise and the sunset.
+const TWILIGHT_TINT: LightColor = LightColor::new(0.25, 0.08, 0.);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TimeOfDay {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The in-game time, it only moves forward with the turns.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Clock {
+    /// How many turns have passed since the start of the game.
+    pub turn: u64,
+}
+
+impl Clock {
+    pub fn tick(&mut self) {
+        self.turn += 1;
+    }
+
+    /// How many turns have passed since midnight.
+    const fn time(&self) -> u64 {
+        (self.turn + START_TURN) % TURNS_PER_DAY
+    }
+
+    pub const fn day(&self) -> u64 {
+        (self.turn + START_TURN) / TURNS_PER_DAY + 1
+    }
+
+    /// The hour of the day, with the fraction of the hour that has passed.
+    fn hours(&self) -> f32 {
+        self.time() as f32 * 24. / TURNS_PER_DAY as f32
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        match self.hours() as u32 {
+            5..=6 => TimeOfDay::Dawn,
+            7..=18 => TimeOfDay::Day,
+            19..=20 => TimeOfDay::Dusk,
+            _ => TimeOfDay::Night,
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.time_of_day() == TimeOfDay::Night
+    }
+
+    /// How bright the sun is, from 0 at night to 1 at day.
+    /// The sun rises from 5 to 7 and sets from 19 to 21.
+    pub fn daylight(&self) -> f32 {
+        let hours = self.hours();
+        if hours < 5. {
+            0.
+        } else if hours < 7. {
+            (hours - 5.) / 2.
+        } else if hours < 19. {
+            1.
+        } else if hours < 21. {
+            (21. - hours) / 2.
+        } else {
+            0.
+        }
+    }
+
+    /// The light that falls on everything the player can see.
+    pub fn ambient_light(&self) -> LightColor {
+        let daylight = self.daylight();
+        // Twilight is strongest halfway between the night and the day.
+        let twilight = 1. - (daylight - 0.5).abs() * 2.;
+        let mut light = NIGHT_AMBIENT.scaled(1. - daylight);
+        light += DAY_AMBIENT.scaled(daylight);
+        light += TWILIGHT_TINT.scaled(twilight);
+        light
+    }
+
+    /// How far the player sees without any light sources.
+    pub fn vision_range(&self) -> i16 {
+        (NIGHT_VISION + (DAY_VISION - NIGHT_VISION) * self.daylight()) as i16
+    }
+}
+
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hours = self.hours();
+        write!(
+            f,
+            "Day {}, {:02}:{:02} ({})",
+            self.day(),
+            hours as u32,
+            (hours.fract() * 60.) as u32,
+            self.time_of_day()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The clock at the hour of the first day.
+    fn at_hour(hour: u64) -> Clock {
+        Clock {
+            turn: (hour * TURNS_PER_DAY / 24 + TURNS_PER_DAY - START_TURN) % TURNS_PER_DAY,
+        }
+    }
+
+    #[test]
+    fn the_game_starts_in_the_day() {
+        assert_eq!(Clock::default().time_of_day(), TimeOfDay::Day);
+        assert_eq!(Clock::default().day(), 1);
+    }
+
+    #[test]
+    fn time_of_day_follows_the_hours() {
+        assert_eq!(at_hour(0).time_of_day(), TimeOfDay::Night);
+        assert_eq!(at_hour(4).time_of_day(), TimeOfDay::Night);
+        assert_eq!(at_hour(5).time_of_day(), TimeOfDay::Dawn);
+        assert_eq!(at_hour(12).time_of_day(), TimeOfDay::Day);
+        assert_eq!(at_hour(19).time_of_day(), TimeOfDay::Dusk);
+        assert_eq!(at_hour(21).time_of_day(), TimeOfDay::Night);
+        assert!(at_hour(23).is_night());
+    }
+}
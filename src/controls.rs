@@ -0,0 +1,510 @@
+use macroquad::prelude::*;
+
+use crate::save::{parse_variant, SaveFile};
+
+use std::collections::HashMap;
+
+/// Something the player can do with the keyboard.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Command {
+    MoveNorth,
+    MoveSouth,
+    MoveWest,
+    MoveEast,
+    MoveNorthWest,
+    MoveNorthEast,
+    MoveSouthWest,
+    MoveSouthEast,
+    /// Held down with a direction to run that way.
+    Run,
+    Wait,
+    PickUp,
+    CloseDoor,
+    Build,
+    ZoomIn,
+    ZoomOut,
+    /// Detach the camera and look around with the cursor.
+    Look,
+    PanNorth,
+    PanSouth,
+    PanWest,
+    PanEast,
+    Inventory,
+    Crafting,
+    Journal,
+    History,
+    /// The full-screen map of the explored world.
+    Map,
+    QuickSave,
+    QuickLoad,
+    /// Pick whatever is under the cursor in the menus: the option, the item, the recipe or the answer.
+    Confirm,
+    /// Drop the item under the cursor in the inventory.
+    Drop,
+    /// Scroll the message log to the older and the newer messages.
+    ScrollUp,
+    ScrollDown,
+    /// Close the open screen, or pause the game when none is open.
+    Cancel,
+    /// Clear the keys of the command in the settings, or erase the typed seed.
+    Clear,
+}
+
+impl Command {
+    pub const ALL: [Self; 33] = [
+        Self::MoveNorth,
+        Self::MoveSouth,
+        Self::MoveWest,
+        Self::MoveEast,
+        Self::MoveNorthWest,
+        Self::MoveNorthEast,
+        Self::MoveSouthWest,
+        Self::MoveSouthEast,
+        Self::Run,
+        Self::Wait,
+        Self::PickUp,
+        Self::CloseDoor,
+        Self::Build,
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::Look,
+        Self::PanNorth,
+        Self::PanSouth,
+        Self::PanWest,
+        Self::PanEast,
+        Self::Inventory,
+        Self::Crafting,
+        Self::Journal,
+        Self::History,
+        Self::Map,
+        Self::QuickSave,
+        Self::QuickLoad,
+        Self::Confirm,
+        Self::Drop,
+        Self::ScrollUp,
+        Self::ScrollDown,
+        Self::Cancel,
+        Self::Clear,
+    ];
+
+    /// The menus can't be used without these, they always keep a key.
+    const ESSENTIAL: [Self; 4] = [
+        Self::MoveNorth,
+        Self::MoveSouth,
+        Self::Confirm,
+        Self::Cancel,
+    ];
+
+    /// The step of the movement command, to the right and up.
+    pub const fn direction(self) -> Option<(i8, i8)> {
+        match self {
+            Self::MoveNorth => Some((0, 1)),
+            Self::MoveSouth => Some((0, -1)),
+            Self::MoveWest => Some((-1, 0)),
+            Self::MoveEast => Some((1, 0)),
+            Self::MoveNorthWest => Some((-1, 1)),
+            Self::MoveNorthEast => Some((1, 1)),
+            Self::MoveSouthWest => Some((-1, -1)),
+            Self::MoveSouthEast => Some((1, -1)),
+            _ => None,
+        }
+    }
+
+    /// The key of the command in the settings file.
+    const fn setting(self) -> &'static str {
+        match self {
+            Self::MoveNorth => "key_move_north",
+            Self::MoveSouth => "key_move_south",
+            Self::MoveWest => "key_move_west",
+            Self::MoveEast => "key_move_east",
+            Self::MoveNorthWest => "key_move_north_west",
+            Self::MoveNorthEast => "key_move_north_east",
+            Self::MoveSouthWest => "key_move_south_west",
+            Self::MoveSouthEast => "key_move_south_east",
+            Self::Run => "key_run",
+            Self::Wait => "key_wait",
+            Self::PickUp => "key_pick_up",
+            Self::CloseDoor => "key_close_door",
+            Self::Build => "key_build",
+            Self::ZoomIn => "key_zoom_in",
+            Self::ZoomOut => "key_zoom_out",
+            Self::Look => "key_look",
+            Self::PanNorth => "key_pan_north",
+            Self::PanSouth => "key_pan_south",
+            Self::PanWest => "key_pan_west",
+            Self::PanEast => "key_pan_east",
+            Self::Inventory => "key_inventory",
+            Self::Crafting => "key_crafting",
+            Self::Journal => "key_journal",
+            Self::History => "key_history",
+            Self::Map => "key_map",
+            Self::QuickSave => "key_quick_save",
+            Self::QuickLoad => "key_quick_load",
+            Self::Confirm => "key_confirm",
+            Self::Drop => "key_drop",
+            Self::ScrollUp => "key_scroll_up",
+            Self::ScrollDown => "key_scroll_down",
+            Self::Cancel => "key_cancel",
+            Self::Clear => "key_clear",
+        }
+    }
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::MoveNorth => "Move north",
+            Self::MoveSouth => "Move south",
+            Self::MoveWest => "Move west",
+            Self::MoveEast => "Move east",
+            Self::MoveNorthWest => "Move north-west",
+            Self::MoveNorthEast => "Move north-east",
+            Self::MoveSouthWest => "Move south-west",
+            Self::MoveSouthEast => "Move south-east",
+            Self::Run => "Run (hold)",
+            Self::Wait => "Wait",
+            Self::PickUp => "Pick up",
+            Self::CloseDoor => "Close door",
+            Self::Build => "Build",
+            Self::ZoomIn => "Zoom in",
+            Self::ZoomOut => "Zoom out",
+            Self::Look => "Look around",
+            Self::PanNorth => "Pan north",
+            Self::PanSouth => "Pan south",
+            Self::PanWest => "Pan west",
+            Self::PanEast => "Pan east",
+            Self::Inventory => "Inventory",
+            Self::Crafting => "Crafting",
+            Self::Journal => "Journal",
+            Self::History => "Message history",
+            Self::Map => "World map",
+            Self::QuickSave => "Quick save",
+            Self::QuickLoad => "Quick load",
+            Self::Confirm => "Confirm / use",
+            Self::Drop => "Drop item",
+            Self::ScrollUp => "Scroll log up",
+            Self::ScrollDown => "Scroll log down",
+            Self::Cancel => "Back / pause",
+            Self::Clear => "Clear / erase",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The keyboard layouts with their own default keys.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Layout {
+    Qwerty,
+    Dvorak,
+    /// QWERTY with the movement on the `hjkl` and `yubn` keys,
+    /// the commands that were on those keys are moved away.
+    Vi,
+}
+
+impl Layout {
+    pub const ALL: [Self; 3] = [Self::Qwerty, Self::Dvorak, Self::Vi];
+
+    /// The default keys for the command. The keys are picked by where they are
+    /// on the keyboard, so the zoom keys sit under the left hand on QWERTY and Dvorak.
+    /// Every layout moves with the arrows and the numpad.
+    fn keys(self, command: Command) -> Vec<KeyCode> {
+        let vi = self == Self::Vi;
+        let with_vi = |mut keys: Vec<KeyCode>, key: KeyCode| {
+            if vi {
+                keys.push(key);
+            }
+            keys
+        };
+        match (command, self) {
+            (Command::MoveNorth, _) => with_vi(vec![KeyCode::Up, KeyCode::Kp8], KeyCode::K),
+            (Command::MoveSouth, _) => with_vi(vec![KeyCode::Down, KeyCode::Kp2], KeyCode::J),
+            (Command::MoveWest, _) => with_vi(vec![KeyCode::Left, KeyCode::Kp4], KeyCode::H),
+            (Command::MoveEast, _) => with_vi(vec![KeyCode::Right, KeyCode::Kp6], KeyCode::L),
+            (Command::MoveNorthWest, _) => with_vi(vec![KeyCode::Kp7], KeyCode::Y),
+            (Command::MoveNorthEast, _) => with_vi(vec![KeyCode::Kp9], KeyCode::U),
+            (Command::MoveSouthWest, _) => with_vi(vec![KeyCode::Kp1], KeyCode::B),
+            (Command::MoveSouthEast, _) => with_vi(vec![KeyCode::Kp3], KeyCode::N),
+            (Command::Run, _) => vec![KeyCode::LeftShift, KeyCode::RightShift],
+            (Command::Wait, Self::Dvorak) => vec![KeyCode::Space, KeyCode::Kp5],
+            (Command::Wait, _) => vec![KeyCode::Space, KeyCode::Kp5, KeyCode::Period],
+            (Command::PickUp, _) => vec![KeyCode::G],
+            (Command::CloseDoor, _) => vec![KeyCode::C],
+            (Command::Build, Self::Vi) => vec![KeyCode::F],
+            (Command::Build, _) => vec![KeyCode::B],
+            (Command::ZoomIn, Self::Qwerty) => vec![KeyCode::E],
+            (Command::ZoomOut, Self::Qwerty) => vec![KeyCode::Q],
+            (Command::ZoomIn, Self::Dvorak) => vec![KeyCode::Period],
+            (Command::ZoomOut, Self::Dvorak) => vec![KeyCode::Apostrophe],
+            (Command::ZoomIn, Self::Vi) => vec![KeyCode::Equal],
+            (Command::ZoomOut, Self::Vi) => vec![KeyCode::Minus],
+            (Command::Look, _) => vec![KeyCode::X],
+            (Command::PanNorth, Self::Dvorak) => vec![KeyCode::Comma],
+            (Command::PanSouth, Self::Dvorak) => vec![KeyCode::O],
+            (Command::PanWest, Self::Dvorak) => vec![KeyCode::A],
+            (Command::PanEast, Self::Dvorak) => vec![KeyCode::E],
+            (Command::PanNorth, _) => vec![KeyCode::W],
+            (Command::PanSouth, _) => vec![KeyCode::S],
+            (Command::PanWest, _) => vec![KeyCode::A],
+            (Command::PanEast, _) => vec![KeyCode::D],
+            (Command::Inventory, _) => vec![KeyCode::I],
+            (Command::Crafting, Self::Vi) => vec![KeyCode::R],
+            (Command::Crafting, _) => vec![KeyCode::K],
+            (Command::Journal, Self::Vi) => vec![KeyCode::Q],
+            (Command::Journal, _) => vec![KeyCode::J],
+            (Command::History, _) => vec![KeyCode::M],
+            (Command::Map, _) => vec![KeyCode::Tab],
+            (Command::QuickSave, _) => vec![KeyCode::F5],
+            (Command::QuickLoad, _) => vec![KeyCode::F9],
+            (Command::Confirm, Self::Vi) => vec![KeyCode::Enter, KeyCode::KpEnter],
+            (Command::Confirm, _) => vec![KeyCode::Enter, KeyCode::KpEnter, KeyCode::U],
+            (Command::Drop, _) => vec![KeyCode::T],
+            (Command::ScrollUp, _) => vec![KeyCode::PageUp],
+            (Command::ScrollDown, _) => vec![KeyCode::PageDown],
+            (Command::Cancel, _) => vec![KeyCode::Escape],
+            (Command::Clear, _) => vec![KeyCode::Backspace, KeyCode::Delete],
+        }
+    }
+}
+
+/// The keys that can be bound to the commands, by their names in the settings file.
+const KEYS: [KeyCode; 94] = [
+    KeyCode::Space,
+    KeyCode::Apostrophe,
+    KeyCode::Comma,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Semicolon,
+    KeyCode::Equal,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::LeftBracket,
+    KeyCode::Backslash,
+    KeyCode::RightBracket,
+    KeyCode::GraveAccent,
+    KeyCode::Escape,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+    KeyCode::KpDecimal,
+    KeyCode::KpDivide,
+    KeyCode::KpMultiply,
+    KeyCode::KpSubtract,
+    KeyCode::KpAdd,
+    KeyCode::KpEnter,
+    KeyCode::LeftShift,
+    KeyCode::RightShift,
+    KeyCode::LeftControl,
+    KeyCode::LeftAlt,
+];
+
+/// Can the key be bound to a command?
+pub fn is_bindable(key: KeyCode) -> bool {
+    KEYS.contains(&key)
+}
+
+/// Repeats the command while its key is held down, first after the delay
+/// and then at the rate, both in seconds.
+#[derive(Debug, Default)]
+pub struct KeyRepeat {
+    held: Option<Command>,
+    /// How long until the next repeat.
+    timer: f32,
+}
+
+impl KeyRepeat {
+    /// The command out of `commands` that was pressed this frame or is due to repeat.
+    /// Only the last pressed command repeats.
+    pub fn update(
+        &mut self,
+        bindings: &KeyBindings,
+        commands: &[Command],
+        delay: f32,
+        rate: f32,
+    ) -> Option<Command> {
+        if let Some(pressed) = commands.iter().rev().find(|c| bindings.pressed(**c)) {
+            self.held = Some(*pressed);
+            self.timer = delay;
+            return Some(*pressed);
+        }
+        let held = self.held.filter(|command| bindings.down(*command));
+        self.held = held;
+        self.timer -= get_frame_time();
+        if held.is_some() && self.timer <= 0. {
+            self.timer = rate;
+            return held;
+        }
+        None
+    }
+}
+
+/// Which keys do which commands, any of the keys of the command will do.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    pub layout: Layout,
+    keys: HashMap<Command, Vec<KeyCode>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::preset(Layout::Qwerty)
+    }
+}
+
+impl KeyBindings {
+    /// The default keys of the layout.
+    pub fn preset(layout: Layout) -> Self {
+        Self {
+            layout,
+            keys: Command::ALL
+                .iter()
+                .map(|command| (*command, layout.keys(*command)))
+                .collect(),
+        }
+    }
+
+    /// Reads the `layout=` line and the keys of the commands, written as
+    /// `key_move_north=Up,Kp8`. The commands without a line get the keys of the layout.
+    pub fn load(file: &SaveFile) -> Self {
+        let layout = file
+            .get("layout")
+            .and_then(|layout| parse_variant(&Layout::ALL, layout))
+            .unwrap_or(Layout::Qwerty);
+        let mut bindings = Self::preset(layout);
+        for command in Command::ALL {
+            if let Some(keys) = file.get(command.setting()) {
+                let keys: Vec<KeyCode> = keys
+                    .split(',')
+                    .filter_map(|key| parse_variant(&KEYS, key.trim()))
+                    .collect();
+                // The menus can't be left without their keys.
+                if !keys.is_empty() || !Command::ESSENTIAL.contains(&command) {
+                    bindings.keys.insert(command, keys);
+                }
+            }
+        }
+        bindings
+    }
+
+    pub fn save(&self, file: &mut SaveFile) {
+        file.set("layout", format!("{:?}", self.layout));
+        for command in Command::ALL {
+            let keys: Vec<String> = self
+                .keys(command)
+                .iter()
+                .map(|key| format!("{:?}", key))
+                .collect();
+            file.set(command.setting(), keys.join(","));
+        }
+    }
+
+    pub fn keys(&self, command: Command) -> &[KeyCode] {
+        self.keys.get(&command).map_or(&[], Vec::as_slice)
+    }
+
+    /// The first key of the command for the help texts.
+    pub fn key_name(&self, command: Command) -> String {
+        self.keys(command)
+            .first()
+            .map_or("unbound".to_string(), |key| format!("{:?}", key))
+    }
+
+    /// Was any of the keys of the command pressed this frame?
+    pub fn pressed(&self, command: Command) -> bool {
+        self.keys(command).iter().any(|key| is_key_pressed(*key))
+    }
+
+    /// Is any of the keys of the command held down?
+    pub fn down(&self, command: Command) -> bool {
+        self.keys(command).iter().any(|key| is_key_down(*key))
+    }
+
+    /// Adds the key to the command, taking it away from any other command.
+    /// The key stays where it is if it's the last key of a command the menus need.
+    pub fn bind(&mut self, command: Command, key: KeyCode) {
+        let needed = Command::ESSENTIAL
+            .iter()
+            .any(|essential| *essential != command && self.keys(*essential) == [key]);
+        if needed {
+            return;
+        }
+        for keys in self.keys.values_mut() {
+            keys.retain(|bound| *bound != key);
+        }
+        self.keys.entry(command).or_default().push(key);
+    }
+
+    /// Takes all the keys away from the command, the commands the menus need keep theirs.
+    pub fn clear(&mut self, command: Command) {
+        if Command::ESSENTIAL.contains(&command) {
+            return;
+        }
+        self.keys.insert(command, Vec::new());
+    }
+}
@@ -51,9 +51,24 @@ impl AbsolutePosition {
             LocalPosition::new(self.local.x + delta.0, self.local.y + delta.1),
         )
     }
+    /// The position of the tile in the whole world, split into the chunk and the local position.
+    pub fn from_absolute_i32((x, y): (i32, i32)) -> Self {
+        let size = i32::from(CHUNK_SIZE);
+        Self {
+            chunk: ChunkPosition::new(x.div_euclid(size), y.div_euclid(size)),
+            local: LocalPosition::new(x.rem_euclid(size) as i16, y.rem_euclid(size) as i16),
+        }
+    }
     pub fn new_local(&self, local: LocalPosition) -> Self {
         get_checked_position(self.chunk, local)
     }
+    /// The position of the tile in the whole world.
+    pub fn get_absolute_position_i32(&self) -> (i32, i32) {
+        (
+            self.chunk.x * i32::from(CHUNK_SIZE) + i32::from(self.local.x),
+            self.chunk.y * i32::from(CHUNK_SIZE) + i32::from(self.local.y),
+        )
+    }
     pub fn get_absolute_position_f32(&self) -> (f32, f32) {
         let LocalPosition { x, y } = self.local;
         let ChunkPosition {
@@ -84,29 +99,50 @@ pub fn distance(first: (f32, f32), second: (f32, f32)) -> f32 {
     distance_x.hypot(distance_y).abs()
 }
 
+/// Moves the local position that went out of its chunk into the right chunk.
+/// Both axes are wrapped on their own, so a diagonal step over the corner
+/// of the chunk ends up in the chunk across the corner.
 pub fn get_checked_position(chunk_pos: ChunkPosition, pos: LocalPosition) -> AbsolutePosition {
     let dimensions = CHUNK_SIZE as i16;
-    let LocalPosition { mut x, mut y } = pos;
-    let ChunkPosition {
-        x: mut world_x,
-        y: mut world_y,
-    } = chunk_pos;
-    if x >= dimensions {
-        world_x += 1;
-        x -= dimensions
-    } else if x < 0 {
-        world_x -= 1;
-        x += dimensions;
+    let LocalPosition { x, y } = pos;
+    AbsolutePosition {
+        local: LocalPosition {
+            x: x.rem_euclid(dimensions),
+            y: y.rem_euclid(dimensions),
+        },
+        chunk: ChunkPosition::new(
+            chunk_pos.x + i32::from(x.div_euclid(dimensions)),
+            chunk_pos.y + i32::from(y.div_euclid(dimensions)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_position_stays_in_the_chunk() {
+        let position = get_checked_position(ChunkPosition::new(2, 3), LocalPosition::new(5, 31));
+        assert_eq!(position.chunk, ChunkPosition::new(2, 3));
+        assert_eq!(position.local, LocalPosition::new(5, 31));
     }
-    if y >= dimensions {
-        world_y += 1;
-        y -= dimensions;
-    } else if y < 0 {
-        world_y -= 1;
-        y += dimensions;
+
+    #[test]
+    fn checked_position_wraps_both_axes() {
+        let position = get_checked_position(ChunkPosition::new(0, 0), LocalPosition::new(-1, 32));
+        assert_eq!(position.chunk, ChunkPosition::new(-1, 1));
+        assert_eq!(position.local, LocalPosition::new(31, 0));
     }
-    AbsolutePosition {
-        local: LocalPosition { x, y },
-        chunk: ChunkPosition::new(world_x, world_y),
+
+    #[test]
+    fn checked_position_wraps_far_steps() {
+        let position = get_checked_position(ChunkPosition::new(0, 0), LocalPosition::new(-33, 70));
+        assert_eq!(position.chunk, ChunkPosition::new(-2, 2));
+        assert_eq!(position.local, LocalPosition::new(31, 6));
+        let (x, y) = position.get_absolute_position_i32();
+        assert_eq!((x, y), (-33, 70));
+        let back = AbsolutePosition::from_absolute_i32((x, y));
+        assert_eq!((back.chunk, back.local), (position.chunk, position.local));
     }
 }
@@ -0,0 +1,102 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::entities::crafting::Recipe;
+use crate::entities::player::{Action, Player};
+
+const LINE_HEIGHT: f32 = 26.;
+const WIDTH: f32 = 560.;
+
+/// The screen listing the recipes, the ones the player has the materials for are highlighted.
+#[derive(Debug, Default)]
+pub struct CraftingScreen {
+    pub open: bool,
+    recipes: Vec<Recipe>,
+    /// The recipe under the cursor.
+    selected: usize,
+}
+
+impl CraftingScreen {
+    pub fn new(recipes: Vec<Recipe>) -> Self {
+        Self {
+            recipes,
+            ..Self::default()
+        }
+    }
+
+    /// Toggles the screen and, while it is open, picks the recipe to craft.
+    /// Returns the action for the player to take.
+    pub fn handle_input(&mut self, player: &Player, bindings: &KeyBindings) -> Option<Action> {
+        if bindings.pressed(Command::Crafting) {
+            self.open = !self.open;
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+        if bindings.pressed(Command::Cancel) {
+            self.open = false;
+            return None;
+        }
+
+        // The cursor moves with the keys that move the player north and south.
+        if bindings.pressed(Command::MoveNorth) {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if bindings.pressed(Command::MoveSouth) {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(self.recipes.len().saturating_sub(1));
+
+        let recipe = self.recipes.get(self.selected)?;
+        if bindings.pressed(Command::Confirm) && recipe.can_craft(&player.inventory) {
+            Some(Action::Craft(recipe.clone()))
+        } else {
+            None
+        }
+    }
+
+    /// Draws the recipes over the screen.
+    pub fn draw(&self, player: &Player, bindings: &KeyBindings) {
+        let (x, y) = (screen_width() / 2. - WIDTH / 2., 60.);
+        let lines = self.recipes.len().max(1) + 3;
+        draw_rectangle(
+            x,
+            y,
+            WIDTH,
+            LINE_HEIGHT * lines as f32,
+            Color::new(0., 0., 0., 0.85),
+        );
+        let mut line = 1.;
+        let mut write = |text: &str, color: Color| {
+            draw_text(text, x + 10., y + LINE_HEIGHT * line, LINE_HEIGHT, color);
+            line += 1.;
+        };
+
+        write("Crafting", WHITE);
+        for (index, recipe) in self.recipes.iter().enumerate() {
+            let marker = if index == self.selected { ">" } else { " " };
+            let color = match (index == self.selected, recipe.can_craft(&player.inventory)) {
+                (true, true) => YELLOW,
+                (true, false) => Color::new(0.6, 0.55, 0.3, 1.),
+                (false, true) => LIGHTGRAY,
+                (false, false) => DARKGRAY,
+            };
+            write(format!("{} {}", marker, recipe).as_str(), color);
+        }
+        if self.recipes.is_empty() {
+            write("  There is nothing to craft.", GRAY);
+        }
+        write(
+            format!(
+                "{}/{} select, {} craft, {} close",
+                bindings.key_name(Command::MoveNorth),
+                bindings.key_name(Command::MoveSouth),
+                bindings.key_name(Command::Confirm),
+                bindings.key_name(Command::Crafting)
+            )
+            .as_str(),
+            GRAY,
+        );
+    }
+}
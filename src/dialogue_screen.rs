@@ -0,0 +1,166 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::coords::AbsolutePosition;
+use crate::entities::dialogue::{DialogueNode, Dialogues, END_NODE, START_NODE};
+use crate::entities::player::Action;
+use crate::save::format_position;
+
+const LINE_HEIGHT: f32 = 26.;
+const WIDTH: f32 = 640.;
+/// The conversation going on with the NPC.
+#[derive(Debug)]
+struct Conversation {
+    npc: &'static str,
+    /// Where the NPC lives, every NPC gives its gifts only once.
+    position: AbsolutePosition,
+    node: String,
+    /// The answer under the cursor.
+    selected: usize,
+}
+
+/// The screen showing what the NPC says and the answers to pick from.
+#[derive(Debug, Default)]
+pub struct DialogueScreen {
+    dialogues: Dialogues,
+    conversation: Option<Conversation>,
+}
+
+impl DialogueScreen {
+    pub fn new(dialogues: Dialogues) -> Self {
+        Self {
+            dialogues,
+            conversation: None,
+        }
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.conversation.is_some()
+    }
+
+    /// Starts the conversation with the NPC living at `position`.
+    /// Returns the action for the effect of the first node.
+    pub fn start(&mut self, npc: &'static str, position: AbsolutePosition) -> Option<Action> {
+        self.conversation = Some(Conversation {
+            npc,
+            position,
+            node: START_NODE.to_string(),
+            selected: 0,
+        });
+        self.effect()
+    }
+
+    /// Moves the cursor over the answers with the north and south keys and picks one.
+    /// Returns the action for the effect of the node the answer leads to.
+    pub fn handle_input(&mut self, bindings: &KeyBindings) -> Option<Action> {
+        if bindings.pressed(Command::Cancel) {
+            self.conversation = None;
+            return None;
+        }
+        let options = self.current_node()?.options.len();
+        let conversation = self.conversation.as_mut()?;
+        if bindings.pressed(Command::MoveNorth) {
+            conversation.selected = conversation.selected.saturating_sub(1);
+        }
+        if bindings.pressed(Command::MoveSouth) {
+            conversation.selected += 1;
+        }
+        conversation.selected = conversation.selected.min(options.saturating_sub(1));
+        if !bindings.pressed(Command::Confirm) {
+            return None;
+        }
+        let chosen = conversation.selected;
+        let next = self.current_node()?.options.get(chosen)?.1.clone();
+        if next == END_NODE {
+            self.conversation = None;
+            return None;
+        }
+        let conversation = self.conversation.as_mut()?;
+        conversation.node = next;
+        conversation.selected = 0;
+        self.effect()
+    }
+
+    /// The action for the effect of the current node,
+    /// tagged with the node and the NPC so it only happens once.
+    fn effect(&self) -> Option<Action> {
+        let conversation = self.conversation.as_ref()?;
+        let effect = self.current_node()?.effect.clone()?;
+        let tag = format!(
+            "{}:{}@{}",
+            conversation.npc,
+            conversation.node,
+            format_position(&conversation.position)
+        );
+        Some(Action::Dialogue(effect, tag))
+    }
+
+    fn current_node(&self) -> Option<&DialogueNode> {
+        let conversation = self.conversation.as_ref()?;
+        self.dialogues.get(conversation.npc, &conversation.node)
+    }
+
+    /// Draws what the NPC says and the answers at the bottom of the screen.
+    pub fn draw(&self, bindings: &KeyBindings) {
+        let (conversation, node) = match (&self.conversation, self.current_node()) {
+            (Some(conversation), Some(node)) => (conversation, node),
+            _ => return,
+        };
+        let text = wrap(&node.text, WIDTH - 20., LINE_HEIGHT);
+        let lines = text.len() + node.options.len() + 4;
+        let height = LINE_HEIGHT * lines as f32;
+        let (x, y) = (
+            screen_width() / 2. - WIDTH / 2.,
+            screen_height() - height - 20.,
+        );
+        draw_rectangle(x, y, WIDTH, height, Color::new(0., 0., 0., 0.85));
+        let mut line = 1.;
+        let mut write = |text: &str, color: Color| {
+            draw_text(text, x + 10., y + LINE_HEIGHT * line, LINE_HEIGHT, color);
+            line += 1.;
+        };
+
+        write(conversation.npc.to_uppercase().as_str(), YELLOW);
+        for text_line in &text {
+            write(text_line, WHITE);
+        }
+        write("", WHITE);
+        for (index, (answer, _)) in node.options.iter().enumerate() {
+            if index == conversation.selected {
+                write(format!("> {}", answer).as_str(), YELLOW);
+            } else {
+                write(format!("  {}", answer).as_str(), LIGHTGRAY);
+            }
+        }
+        write(
+            format!(
+                "{}/{} select, {} answer, {} leave",
+                bindings.key_name(Command::MoveNorth),
+                bindings.key_name(Command::MoveSouth),
+                bindings.key_name(Command::Confirm),
+                bindings.key_name(Command::Cancel)
+            )
+            .as_str(),
+            GRAY,
+        );
+    }
+}
+
+/// Splits the text into the lines that fit into `width` at the font size.
+pub fn wrap(text: &str, width: f32, font_size: f32) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().expect("There is always a line!");
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if !line.is_empty() && measure_text(&candidate, None, font_size as u16, 1.).width > width {
+            lines.push(word.to_string());
+        } else {
+            *line = candidate;
+        }
+    }
+    lines
+}
@@ -0,0 +1,43 @@
+/// The amount of energy an action costs.
+pub const ACTION_COST: i32 = 100;
+/// Speed of a normal creature, it gets to act exactly once per turn.
+pub const NORMAL_SPEED: i32 = 100;
+
+/// Anything that can act in the game world.
+/// Every turn actors accumulate energy according to their speed
+/// and are allowed to act while they have enough energy for an action.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Actor {
+    pub energy: i32,
+    pub speed: i32,
+}
+
+impl Actor {
+    pub const fn new(speed: i32) -> Self {
+        Self { energy: 0, speed }
+    }
+
+    /// Adds the energy for one turn.
+    pub fn gain_energy(&mut self) {
+        self.energy += self.speed;
+    }
+
+    /// Spends the energy for one action.
+    pub fn spend_energy(&mut self) {
+        self.energy -= ACTION_COST;
+    }
+
+    /// Is there enough energy to act?
+    pub const fn is_ready(&self) -> bool {
+        self.energy >= ACTION_COST
+    }
+}
+
+impl Default for Actor {
+    fn default() -> Self {
+        Self {
+            energy: ACTION_COST,
+            speed: NORMAL_SPEED,
+        }
+    }
+}
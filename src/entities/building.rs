@@ -0,0 +1,132 @@
+use crate::entities::entities::{Entities, Entity};
+use crate::entities::equipment::EquipmentSlot;
+use crate::entities::items::{Item, ItemKind};
+use crate::message_log::MessageKind;
+use crate::tile_types::TileType;
+use crate::world::World;
+
+/// How much wood a wooden wall takes to build.
+pub(super) const WALL_COST: u32 = 2;
+
+impl Entities {
+    /// Opens the closed door at `index`, locked doors need a key.
+    /// Returns whether the door was opened.
+    pub(super) fn open_door(&mut self, index: usize) -> bool {
+        let door = &mut self.entities[index];
+        match door.tile {
+            TileType::Door => {
+                door.set_tile(TileType::OpenDoor);
+                self.events.message(MessageKind::Info, "You open the door.");
+                true
+            }
+            TileType::LockedDoor => match self.player.inventory.position(ItemKind::Key) {
+                Some(key) => {
+                    self.player.inventory.take(key, 1);
+                    door.set_tile(TileType::OpenDoor);
+                    self.events
+                        .message(MessageKind::Discovery, "You unlock the door with a key.");
+                    true
+                }
+                None => {
+                    self.events
+                        .message(MessageKind::Warning, "The door is locked.");
+                    false
+                }
+            },
+            _ => false,
+        }
+    }
+
+    /// Closes an open door next to the player, unless something is in the way.
+    /// Returns whether a door was closed.
+    pub(super) fn close_door(&mut self) -> bool {
+        let player_pos = self.player.entity.get_absolute_position();
+        let neighbours = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        for delta in neighbours {
+            let position = player_pos.add_to_local(delta);
+            let door = self
+                .entities
+                .iter()
+                .position(|e| e.tile == TileType::OpenDoor && e.is_at(&position));
+            if let Some(index) = door {
+                if self.entities.iter().filter(|e| e.is_at(&position)).count() > 1 {
+                    self.events
+                        .message(MessageKind::Info, "Something is in the way.");
+                    return false;
+                }
+                self.entities[index].set_tile(TileType::Door);
+                self.events
+                    .message(MessageKind::Info, "You close the door.");
+                return true;
+            }
+        }
+        self.events
+            .message(MessageKind::Info, "There is no open door next to you.");
+        false
+    }
+
+    /// Cuts down the plant or the wooden wall at `index` for the wood.
+    pub(super) fn cut(&mut self, index: usize) {
+        let entity = &mut self.entities[index];
+        entity.removed = true;
+        let wood = Item::new(ItemKind::Wood, Entity::wood_yield(entity.tile));
+        self.events.message(
+            MessageKind::Pickup,
+            format!("You cut down the {} and get {}.", entity.tile, wood),
+        );
+        self.stash(wood);
+    }
+
+    /// Digs through the stone wall at `index`, only possible with a pickaxe.
+    /// The ground under the wall is left as a bare stone floor.
+    pub(super) fn dig(&mut self, index: usize, world: &mut World) -> bool {
+        if self.player.equipment.get(EquipmentSlot::Weapon) != Some(ItemKind::Pickaxe) {
+            self.events.message(
+                MessageKind::Info,
+                "You need a pickaxe to dig through the wall.",
+            );
+            return false;
+        }
+        let wall = &mut self.entities[index];
+        wall.removed = true;
+        world.set_tile(&wall.get_absolute_position(), TileType::StoneFloor);
+        self.events
+            .message(MessageKind::Info, "You dig through the stone wall.");
+        true
+    }
+
+    /// Builds a wall on the free tile in front of the player,
+    /// out of a stone block if there is one or out of the wood otherwise.
+    pub(super) fn build(&mut self, world: &World) -> bool {
+        let inventory = &self.player.inventory;
+        let (material, wall) = if inventory.count(ItemKind::StoneBlock) > 0 {
+            (Item::new(ItemKind::StoneBlock, 1), TileType::StoneWall)
+        } else if inventory.count(ItemKind::Wood) >= WALL_COST {
+            (Item::new(ItemKind::Wood, WALL_COST), TileType::WoodenWall)
+        } else {
+            self.events.message(
+                MessageKind::Info,
+                format!(
+                    "You need a stone block or {} wood to build a wall.",
+                    WALL_COST
+                ),
+            );
+            return false;
+        };
+        let position = self
+            .player
+            .entity
+            .get_absolute_position()
+            .add_to_local(self.player.facing);
+        if self.get_entity_at_pos(&position).is_some() || !world.is_walkable(&position) {
+            self.events
+                .message(MessageKind::Info, "There is no room to build a wall there.");
+            return false;
+        }
+        self.take_items(material);
+        self.entities.push(Entity::new(position, wall));
+        self.events
+            .message(MessageKind::Info, format!("You build a {}.", wall));
+        true
+    }
+}
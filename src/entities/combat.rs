@@ -0,0 +1,124 @@
+use crate::entities::entities::{Entities, Entity};
+use crate::entities::equipment::EquipmentSlot;
+use crate::entities::items::ItemKind;
+use crate::events::GameEvent;
+use crate::message_log::MessageKind;
+use crate::rng::Rng;
+use crate::tile_types::TileType;
+
+/// The percent chance for the creature's hit to leave its status effect.
+const INFLICT_CHANCE: i32 = 35;
+
+/// Hit points and fighting capabilities of a creature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Stats {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub attack: i32,
+    pub defense: i32,
+}
+
+impl Stats {
+    pub const fn new(max_hp: i32, attack: i32, defense: i32) -> Self {
+        Self {
+            hp: max_hp,
+            max_hp,
+            attack,
+            defense,
+        }
+    }
+
+    pub const fn is_dead(&self) -> bool {
+        self.hp <= 0
+    }
+
+    /// Rolls the damage of an attack against the `defender`,
+    /// anything from half to the full attack, reduced by the defense.
+    pub fn roll_damage(&self, defender: &Self, rng: &mut Rng) -> i32 {
+        let roll = rng.range((self.attack + 1) / 2, self.attack + 1);
+        (roll - defender.defense).max(0)
+    }
+
+    /// Returns whether the hit was deadly.
+    pub fn take_damage(&mut self, damage: i32) -> bool {
+        self.hp -= damage;
+        self.is_dead()
+    }
+}
+
+impl Entities {
+    /// The player hits the creature at `index`, the creature may drop loot on death.
+    pub(super) fn player_attacks(&mut self, index: usize) {
+        let creature = &mut self.entities[index];
+        let tile = creature.tile;
+        let stats = creature
+            .stats
+            .as_mut()
+            .expect("Only creatures can be attacked!");
+        let damage = self.player.combat_stats().roll_damage(stats, &mut self.rng);
+        if !stats.take_damage(damage) {
+            self.events.message(
+                MessageKind::Combat,
+                format!("You hit the {} for {} damage.", tile, damage),
+            );
+            // Some of the weapons leave more than the wounds too.
+            let weapon = self.player.equipment.get(EquipmentSlot::Weapon);
+            if let Some(effect) = weapon.and_then(ItemKind::inflicts) {
+                if damage > 0 && self.rng.chance(INFLICT_CHANCE) {
+                    self.events.message(
+                        MessageKind::Combat,
+                        format!("Your hit gives the {} {}.", tile, effect.kind),
+                    );
+                    creature.status.add(effect);
+                }
+            }
+            return;
+        }
+
+        creature.removed = true;
+        let position = creature.get_absolute_position();
+        self.events
+            .message(MessageKind::Combat, format!("You kill the {}!", tile));
+        self.events.emit(GameEvent::EntityDied { tile });
+        if self.rng.chance(Entity::drop_chance(tile)) {
+            self.entities.push(Entity::new(position, TileType::Coin));
+            self.events
+                .message(MessageKind::Info, format!("The {} drops a coin.", tile));
+        }
+    }
+
+    /// The creature at `index` hits the player.
+    pub(super) fn creature_attacks(&mut self, index: usize) {
+        let creature = &self.entities[index];
+        let stats = creature.stats.expect("Only creatures can attack!");
+        let damage = stats.roll_damage(&self.player.combat_stats(), &mut self.rng);
+        self.events.message(
+            MessageKind::Combat,
+            format!("The {} hits you for {} damage.", creature.tile, damage),
+        );
+        if self.player.stats.take_damage(damage) {
+            self.player_dies(format!("Killed by a {}", creature.tile));
+            return;
+        }
+        // Some of the creatures leave more than the wounds.
+        if let Some(effect) = Entity::inflicts(creature.tile) {
+            if damage > 0 && self.rng.chance(INFLICT_CHANCE) {
+                self.events.message(
+                    MessageKind::Warning,
+                    format!("The {}'s hit gives you {}.", creature.tile, effect.kind),
+                );
+                self.player.status.add(effect);
+            }
+        }
+    }
+
+    /// Remembers what killed the player, only the first cause counts.
+    pub(super) fn player_dies(&mut self, cause: String) {
+        if self.player.cause_of_death.is_none() {
+            self.player.cause_of_death = Some(cause);
+            self.events.emit(GameEvent::EntityDied {
+                tile: self.player.entity.tile,
+            });
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::entities::entities::Entities;
+use crate::entities::inventory::Inventory;
+use crate::entities::items::{Item, ItemKind};
+use crate::message_log::MessageKind;
+use crate::save::parse_variant;
+
+/// Where the recipes are loaded from.
+pub const RECIPES_PATH: &str = "assets/recipes.txt";
+
+/// What it takes to make an item.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Recipe {
+    pub output: Item,
+    pub inputs: Vec<Item>,
+}
+
+impl Recipe {
+    /// Are there enough of the inputs in the inventory?
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        self.inputs
+            .iter()
+            .all(|input| inventory.count(input.kind) >= input.count)
+    }
+}
+
+impl std::fmt::Display for Recipe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inputs: Vec<String> = self.inputs.iter().map(Item::to_string).collect();
+        write!(f, "{} <- {}", self.output, inputs.join(", "))
+    }
+}
+
+/// Parses the `Kind:count` pair.
+fn parse_item(value: &str) -> Option<Item> {
+    let (kind, count) = value.trim().split_once(':')?;
+    let kind = parse_variant(&ItemKind::ALL, kind.trim())?;
+    Some(Item::new(kind, count.trim().parse().ok()?))
+}
+
+/// Parses the recipes, one per line as `output:count=input:count,input:count`.
+/// Empty lines and the lines starting with `#` are skipped.
+pub fn parse_recipes(text: &str) -> Option<Vec<Recipe>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (output, inputs) = line.split_once('=')?;
+            Some(Recipe {
+                output: parse_item(output)?,
+                inputs: inputs.split(',').map(parse_item).collect::<Option<_>>()?,
+            })
+        })
+        .collect()
+}
+
+impl Entities {
+    /// Makes the item from the recipe if the player has the materials.
+    pub(super) fn craft(&mut self, recipe: &Recipe) -> bool {
+        if !recipe.can_craft(&self.player.inventory) {
+            self.events.message(
+                MessageKind::Info,
+                format!(
+                    "You don't have the materials for the {}.",
+                    recipe.output.kind
+                ),
+            );
+            return false;
+        }
+        for input in &recipe.inputs {
+            self.take_items(*input);
+        }
+        self.events.message(
+            MessageKind::Pickup,
+            format!("You craft a {}.", recipe.output),
+        );
+        self.stash(recipe.output);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_recipes_reads_every_line() {
+        let recipes = parse_recipes("# Recipes.\nTorch:1=Wood:1,Stone:1\n\nStoneBlock:1=Stone:3")
+            .expect("The recipes are valid!");
+        assert_eq!(
+            recipes,
+            vec![
+                Recipe {
+                    output: Item::new(ItemKind::Torch, 1),
+                    inputs: vec![Item::new(ItemKind::Wood, 1), Item::new(ItemKind::Stone, 1)],
+                },
+                Recipe {
+                    output: Item::new(ItemKind::StoneBlock, 1),
+                    inputs: vec![Item::new(ItemKind::Stone, 3)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_recipes_rejects_the_broken_lines() {
+        assert!(parse_recipes("Torch:1").is_none());
+        assert!(parse_recipes("Torch:1=Wood").is_none());
+        assert!(parse_recipes("Cake:1=Flower:2").is_none());
+    }
+
+    #[test]
+    fn can_craft_needs_every_input() {
+        let recipe = Recipe {
+            output: Item::new(ItemKind::Torch, 1),
+            inputs: vec![Item::new(ItemKind::Wood, 1), Item::new(ItemKind::Stone, 1)],
+        };
+        let mut inventory = Inventory::default();
+        inventory.add(Item::new(ItemKind::Wood, 1));
+        assert!(!recipe.can_craft(&inventory));
+        inventory.add(Item::new(ItemKind::Stone, 1));
+        assert!(recipe.can_craft(&inventory));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::coords::distance;
+use crate::entities::entities::Entities;
+use crate::entities::items::{Item, ItemKind};
+use crate::message_log::MessageKind;
+use crate::save::parse_variant;
+use crate::tile_types::TileType;
+
+use std::collections::HashMap;
+
+/// Where the dialogue trees are loaded from.
+pub const DIALOGUE_PATH: &str = "assets/dialogue.txt";
+/// The node every conversation starts at.
+pub const START_NODE: &str = "start";
+/// The next node that closes the conversation.
+pub const END_NODE: &str = "end";
+
+/// What reaching a dialogue node does to the game.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DialogueEffect {
+    /// The NPC gives the items to the player.
+    Give(Item),
+    /// The NPC points out the nearest entity with this tile the player doesn't see.
+    Reveal(TileType),
+    /// The NPC offers the quest with this id.
+    StartQuest(String),
+}
+
+/// One thing the NPC says, with the answers leading to the other nodes.
+#[derive(Debug, Clone, Default)]
+pub struct DialogueNode {
+    pub text: String,
+    /// The answer and the id of the node it leads to.
+    pub options: Vec<(String, String)>,
+    pub effect: Option<DialogueEffect>,
+}
+
+/// The dialogue trees of all the NPCs, the nodes are found by `npc:node`.
+#[derive(Debug, Clone, Default)]
+pub struct Dialogues {
+    nodes: HashMap<String, DialogueNode>,
+}
+
+impl Dialogues {
+    pub fn get(&self, npc: &str, node: &str) -> Option<&DialogueNode> {
+        self.nodes.get(&format!("{}:{}", npc, node))
+    }
+
+    /// Parses the dialogue trees. Every node starts with the `[npc:node]` line
+    /// followed by the `text=`, `option=answer|next` and `effect=` lines.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut dialogues = Self::default();
+        let mut current = None;
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(id) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                dialogues
+                    .nodes
+                    .insert(id.to_string(), DialogueNode::default());
+                current = Some(id.to_string());
+                continue;
+            }
+            let node = dialogues.nodes.get_mut(current.as_ref()?)?;
+            match line.split_once('=')? {
+                ("text", text) => node.text = text.to_string(),
+                ("option", option) => {
+                    let (answer, next) = option.split_once('|')?;
+                    node.options.push((answer.to_string(), next.to_string()));
+                }
+                ("effect", effect) => node.effect = Some(parse_effect(effect)?),
+                _ => return None,
+            }
+        }
+        Some(dialogues)
+    }
+}
+
+/// Parses `give:Kind:count`, `reveal:Tile` or `quest:id`.
+fn parse_effect(value: &str) -> Option<DialogueEffect> {
+    match value.split(':').collect::<Vec<&str>>().as_slice() {
+        ["give", kind, count] => Some(DialogueEffect::Give(Item::new(
+            parse_variant(&ItemKind::ALL, kind)?,
+            count.parse().ok()?,
+        ))),
+        ["reveal", tile] => Some(DialogueEffect::Reveal(parse_variant(&TileType::ALL, tile)?)),
+        ["quest", id] => Some(DialogueEffect::StartQuest(id.to_string())),
+        _ => None,
+    }
+}
+
+impl Entities {
+    /// Does what the NPC promised in the conversation.
+    pub(super) fn apply_dialogue_effect(&mut self, effect: DialogueEffect) {
+        match effect {
+            DialogueEffect::Give(item) => {
+                self.events
+                    .message(MessageKind::Pickup, format!("You get a {}.", item));
+                self.stash(item);
+            }
+            DialogueEffect::Reveal(tile) => {
+                let player_pos = self.player.entity.get_absolute_position_f32();
+                let hidden = self.entities.iter().filter(|e| {
+                    let position = e.get_absolute_position().get_absolute_position_i32();
+                    e.tile == tile && !e.removed && !self.fov.is_visible(position)
+                });
+                let nearest = hidden.min_by(|a, b| {
+                    let distance_a = distance(a.get_absolute_position_f32(), player_pos);
+                    let distance_b = distance(b.get_absolute_position_f32(), player_pos);
+                    distance_a.total_cmp(&distance_b)
+                });
+                match nearest {
+                    Some(entity) => {
+                        self.markers.push(entity.get_absolute_position());
+                        self.events.message(
+                            MessageKind::Discovery,
+                            format!("You learn where to find a {}.", tile),
+                        );
+                    }
+                    None => self
+                        .events
+                        .message(MessageKind::Info, "There is nothing like that around."),
+                }
+            }
+            DialogueEffect::StartQuest(id) => self.start_quest(&id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_the_nodes() {
+        let dialogues = Dialogues::parse(
+            "[cat:start]\ntext=Meow?\noption=Pet it.|pet\noption=Leave.|end\n\
+             [cat:pet]\ntext=Purr.\neffect=give:Flower:1",
+        )
+        .expect("The dialogue is valid!");
+        let start = dialogues.get("cat", START_NODE).expect("There is a start!");
+        assert_eq!(start.text, "Meow?");
+        assert_eq!(
+            start.options,
+            vec![
+                ("Pet it.".to_string(), "pet".to_string()),
+                ("Leave.".to_string(), END_NODE.to_string())
+            ]
+        );
+        let pet = dialogues.get("cat", "pet").expect("There is a pet node!");
+        assert_eq!(
+            pet.effect,
+            Some(DialogueEffect::Give(Item::new(ItemKind::Flower, 1)))
+        );
+        assert!(dialogues.get("rat", START_NODE).is_none());
+    }
+
+    #[test]
+    fn parse_rejects_the_broken_dialogues() {
+        assert!(Dialogues::parse("text=Nobody says this.").is_none());
+        assert!(Dialogues::parse("[cat:start]\noption=No next node").is_none());
+        assert!(Dialogues::parse("[cat:start]\neffect=dance").is_none());
+    }
+}
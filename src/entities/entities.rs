@@ -1,34 +1,205 @@
+use crate::clock::Clock;
 use crate::coords::{
     distance, get_checked_position, AbsolutePosition, ChunkPosition, LocalPosition, CHUNK_SIZE,
 };
-use crate::entities::player::Player;
+use crate::entities::actor::{Actor, NORMAL_SPEED};
+use crate::entities::building::WALL_COST;
+use crate::entities::combat::Stats;
+use crate::entities::items::{Item, ItemKind};
+use crate::entities::lore::Lore;
+use crate::entities::player::{Action, Player};
+use crate::entities::quests::QuestBook;
+use crate::entities::status::{StatusEffect, StatusEffects, StatusKind};
+use crate::events::{EventBus, GameEvent};
+use crate::fov::FieldOfView;
 use crate::generator::Generator;
 use crate::graphics::chunk_terrain::ChunkTerrain;
+use crate::graphics::lighting::{Light, LightColor, LightMap};
 use crate::graphics::tile_atlas::TileAtlas;
+use crate::map_memory::{MapMemory, Remembered};
+use crate::message_log::MessageKind;
+use crate::rng::Rng;
+use crate::save::{format_position, parse_list, parse_position, parse_variant, SaveFile};
 use crate::tile_types::TileType;
+use crate::weather::{Weather, WeatherKind};
 use crate::world::World;
 
-use std::collections::HashMap;
+/// How far the player can see lit places.
+pub(super) const MAX_SIGHT: i16 = 24;
+/// How many tiles across a hut is.
+pub(super) const HUT_SIZE: i16 = 5;
+/// The percent chance for the confused player to stumble in a random direction.
+pub(super) const STUMBLE_CHANCE: i32 = 50;
+/// What the hallucinating player sees instead of the creatures and the items.
+const HALLUCINATIONS: [TileType; 6] = [
+    TileType::Pengu,
+    TileType::Cat,
+    TileType::Ghost,
+    TileType::Chest,
+    TileType::MoonFlower,
+    TileType::Slime,
+];
+
+use macroquad::prelude::{draw_rectangle_lines, YELLOW};
+use std::collections::{HashMap, HashSet};
+
+/// The run the player is on, one step at a time until something interesting happens.
+#[derive(Debug)]
+struct Run {
+    direction: (i8, i8),
+    /// The terrain the run started on, the run stops where it changes.
+    terrain: Option<TileType>,
+    /// Where the items in view were, the run stops when a new one is seen.
+    seen_items: HashSet<(i32, i32)>,
+    /// The run stops when the player gets hurt.
+    hp: i32,
+    /// Were both sides blocked after the last step? The run stops where the corridor branches.
+    in_corridor: bool,
+}
+
 /// Entities have the capabilites to interact in the game world.
 /// Block view, move, block movement, be playable...
 #[derive(Debug, Default)]
 pub struct Entities {
     pub player: Player,
+    /// The in-game time, advanced by the turns.
+    pub clock: Clock,
+    /// The seed of the game, the weather is decided by it.
+    pub(super) seed: i32,
+    /// Rolls the dice for everything that happens to the entities.
+    pub(super) rng: Rng,
+    /// The weather where the player is.
+    pub weather: Weather,
+    /// The NPC the player has just bumped into, and where it lives.
+    pub talking_to: Option<(&'static str, AbsolutePosition)>,
+    /// The places the NPCs pointed out, until the player sees them.
+    pub markers: Vec<AbsolutePosition>,
+    /// All the quests there are to give.
+    pub(super) quest_book: QuestBook,
+    /// The writings of the engraved stones.
+    pub(super) lore: Lore,
+    /// What happened during the updates, for the other systems to react to.
+    pub events: EventBus,
+    /// The run the player is on.
+    run: Option<Run>,
+    /// What the player is able to see.
+    pub fov: FieldOfView,
+    /// What the player has seen.
+    pub memory: MapMemory,
+    /// The sight and the light have to be worked out again, nothing changed otherwise.
+    pub(super) view_changed: bool,
 
-    entities: Vec<Entity>,
+    pub(super) entities: Vec<Entity>,
 
-    loaded_locations: Vec<ChunkPosition>,
-    entities_store: HashMap<ChunkPosition, Vec<Entity>>,
+    pub(super) loaded_locations: Vec<ChunkPosition>,
+    pub(super) entities_store: HashMap<ChunkPosition, Vec<Entity>>,
 }
 
 impl Entities {
-    pub fn input(&mut self, dest: (i8, i8)) {
-        self.player.destination.set_destination(dest.0, dest.1);
+    pub fn new(seed: i32, quest_book: QuestBook, lore: Lore) -> Self {
+        Self {
+            seed,
+            quest_book,
+            lore,
+            rng: Rng::new(seed as u64),
+            view_changed: true,
+            ..Self::default()
+        }
     }
-    pub fn update(&mut self, world: &World, generator: &Generator) {
+
+    pub const fn seed(&self) -> i32 {
+        self.seed
+    }
+
+    /// Writes down the time, the weather, the player and every entity,
+    /// the terrain is generated again from the seed.
+    pub fn save(&self, file: &mut SaveFile) {
+        file.push("seed", self.seed);
+        file.push("turn", self.clock.turn);
+        file.push(
+            "weather",
+            format!("{:?},{}", self.weather.kind, self.weather.intensity),
+        );
+        self.player.save(file);
+        for marker in &self.markers {
+            file.push("marker", format_position(marker));
+        }
+        for location in self
+            .loaded_locations
+            .iter()
+            .chain(self.entities_store.keys())
+        {
+            file.push("location", format!("{},{}", location.x, location.y));
+        }
+        let stored = self.entities_store.values().flatten();
+        for entity in self.entities.iter().chain(stored).filter(|e| !e.removed) {
+            file.push("entity", entity.save_string());
+        }
+        self.memory.save(file);
+    }
+
+    /// Restores the entities written down by `save`.
+    pub fn load(file: &SaveFile, quest_book: QuestBook, lore: Lore) -> Option<Self> {
+        let seed = file.parse("seed")?;
+        let mut entities = Self::new(seed, quest_book, lore);
+        entities.clock.turn = file.parse("turn")?;
+        // The dice shouldn't roll the same way they did at the start.
+        entities.rng = Rng::new(seed as u64 ^ entities.clock.turn);
+        if let Some((kind, intensity)) = file.get("weather").and_then(|w| w.split_once(',')) {
+            entities.weather = Weather::new(
+                parse_variant(&WeatherKind::ALL, kind)?,
+                intensity.parse().ok()?,
+            );
+        }
+        entities.player = Player::load(file)?;
+        entities.markers = file
+            .get_all("marker")
+            .map(parse_position)
+            .collect::<Option<_>>()?;
+        entities.memory = MapMemory::load(file)?;
+
+        // The chunks that were visited get their entities from the save,
+        // even if there are none left.
+        for location in file.get_all("location") {
+            if let [x, y] = parse_list(location)?.as_slice() {
+                entities
+                    .entities_store
+                    .insert(ChunkPosition::new(*x, *y), Vec::new());
+            }
+        }
+        for value in file.get_all("entity") {
+            let entity = Entity::from_save_string(value)?;
+            entities
+                .entities_store
+                .entry(entity.chunk_pos)
+                .or_default()
+                .push(entity);
+        }
+        Some(entities)
+    }
+
+    pub fn input(&mut self, action: Option<Action>) {
+        if action.is_some() {
+            // Doing anything else stops the run.
+            if !matches!(action, Some(Action::Run(..))) {
+                self.run = None;
+            }
+            self.player.action = action;
+        }
+    }
+
+    /// The next step of the run the player is on, if any.
+    pub fn next_run_step(&self) -> Option<Action> {
+        self.run
+            .as_ref()
+            .map(|run| Action::Run(run.direction.0, run.direction.1))
+    }
+    /// Returns whether the player's view changed, the light has to be worked out again then.
+    pub fn update(&mut self, world: &mut World, generator: &Generator) -> bool {
         let active_locations = &world.positions_of_chunks_in_view;
         for active_location in active_locations.iter() {
             if !self.loaded_locations.contains(active_location) {
+                self.view_changed = true;
                 self.load_entities_at_location(
                     active_location,
                     world
@@ -47,48 +218,351 @@ impl Entities {
         }
 
         for location in locations_to_unload.drain(..) {
+            self.view_changed = true;
             self.unload_entites_from_location(&location);
         }
         self.clean_up();
-        if !self.player.destination.is_zero() {
-            let future_pos = self.player.calc_future_pos();
-            let collider = self
-                .entities
-                .iter_mut()
-                .find(|e| e.pos == future_pos.local && e.chunk_pos == future_pos.chunk);
-
-            let allowed_to_move = match collider {
-                Some(collider) => {
-                    if Entity::is_blocking(collider).unwrap() {
-                        false
-                    } else {
-                        collider.collide(&mut self.player);
-                        true
-                    }
+
+        // The world only advances when the player does something.
+        if let Some(action) = self.player.action.take() {
+            if self.player.is_dead() {
+                return false;
+            }
+            if self.perform_player_action(action, world) {
+                self.view_changed = true;
+                self.player.actor.spend_energy();
+                let turn = self.clock.turn;
+                self.run_turns(world);
+                let turns_passed = self.clock.turn - turn;
+                if self.clock.is_night() {
+                    self.spawn_night_creatures(world, generator, turns_passed);
                 }
-                None => true,
-            };
+            }
+        }
+        self.hand_out_rewards();
+        // The sight only changes when a turn passes or the chunks around the player change.
+        if !std::mem::take(&mut self.view_changed) {
+            return false;
+        }
+        self.player.vision_range = self.clock.vision_range();
+        self.update_fov();
+        self.remember_what_is_seen(world);
+        // The places are found once the player sees them.
+        let fov = &self.fov;
+        self.markers
+            .retain(|marker| !fov.is_visible(marker.get_absolute_position_i32()));
+        true
+    }
 
-            if allowed_to_move {
-                self.player
-                    .entity
-                    .add_to_local_position(self.player.destination.as_tuple());
+    /// Updates what the player is able to see, the tiles still need the light to be seen.
+    fn update_fov(&mut self) {
+        let blockers = self.sight_blockers();
+        let origin = self
+            .player
+            .entity
+            .get_absolute_position()
+            .get_absolute_position_i32();
+        // In the daylight everything is visible far away,
+        // at night the player only sees as far as the lights go.
+        let daylight_sight = (f32::from(MAX_SIGHT) * self.clock.daylight()) as i16;
+        let sight = self
+            .weather
+            .limit_sight(daylight_sight.max(self.player.vision()));
+        self.fov
+            .compute(origin, sight, |position| blockers.contains(&position));
+    }
+
+    /// Remembers the terrain and the entities on every tile in view.
+    fn remember_what_is_seen(&mut self, world: &World) {
+        let mut seen: HashMap<(i32, i32), Vec<TileType>> = HashMap::new();
+        for entity in self.entities.iter().filter(|e| !e.removed) {
+            let position = entity.get_absolute_position().get_absolute_position_i32();
+            if self.fov.is_visible(position) {
+                seen.entry(position).or_default().push(entity.tile);
+            }
+        }
+        for position in self.fov.iter() {
+            let terrain = world.get_tile(&AbsolutePosition::from_absolute_i32(*position));
+            if let Some(terrain) = terrain {
+                self.memory.remember(
+                    *position,
+                    Remembered {
+                        terrain: *terrain,
+                        entities: seen.remove(position).unwrap_or_default(),
+                        turn: self.clock.turn,
+                    },
+                );
             }
-            self.player.destination.reset_destination();
         }
     }
 
-    pub fn draw(&self, tile_atlas: &TileAtlas) {
-        let player_pos = self.player.entity.get_absolute_position_f32();
+    /// The positions of the walls and closed doors, they block the sight and the light.
+    pub fn sight_blockers(&self) -> HashSet<(i32, i32)> {
+        self.entities
+            .iter()
+            .filter(|e| Entity::is_blocking_sight(e))
+            .map(|e| e.get_absolute_position().get_absolute_position_i32())
+            .collect()
+    }
+
+    /// The player's own light and everything glowing around.
+    pub fn light_sources(&self) -> Vec<((i32, i32), Light)> {
+        let player_light = Light::new(self.player.vision(), LightColor::WHITE);
+        let player_pos = self
+            .player
+            .entity
+            .get_absolute_position()
+            .get_absolute_position_i32();
+        let mut lights = vec![(player_pos, player_light)];
+        for entity in &self.entities {
+            if let Some(light) = Light::emitted_by(entity.tile) {
+                let position = entity.get_absolute_position().get_absolute_position_i32();
+                lights.push((position, light));
+            }
+        }
+        lights
+    }
+
+    /// Does the action for the player.
+    /// Returns whether the action took a turn.
+    fn perform_player_action(&mut self, action: Action, world: &mut World) -> bool {
+        match action {
+            Action::Move(mut right, mut up) => {
+                if self.player.status.has(StatusKind::Confusion) && self.rng.chance(STUMBLE_CHANCE)
+                {
+                    (right, up) = [
+                        (1, 0),
+                        (1, 1),
+                        (0, 1),
+                        (-1, 1),
+                        (-1, 0),
+                        (-1, -1),
+                        (0, -1),
+                        (1, -1),
+                    ][self.rng.range(0, 8) as usize];
+                    self.events
+                        .message(MessageKind::Info, "You stumble around in confusion.");
+                }
+                self.player.destination.set_destination(right, up);
+                let moved = !self.player.destination.is_zero() && self.move_player(world);
+                self.player.destination.reset_destination();
+                moved
+            }
+            Action::Run(right, up) => self.keep_running(right, up, world),
+            Action::Wait => true,
+            Action::PickUp => self.pick_up_here(),
+            Action::CloseDoor => self.close_door(),
+            Action::Build => self.build(world),
+            Action::Craft(recipe) => self.craft(&recipe),
+            Action::Dialogue(effect, tag) => {
+                if self.player.dialogue_flags.insert(tag) {
+                    self.apply_dialogue_effect(effect);
+                }
+                false
+            }
+            Action::UseItem(index) => self.use_item(index),
+            Action::DropItem(index) => self.drop_item(index),
+            Action::Unequip(slot) => self.unequip(slot),
+        }
+    }
+
+    /// Takes the next step of the run, starting the run if there is none.
+    /// Returns whether the step took a turn.
+    fn keep_running(&mut self, right: i8, up: i8, world: &mut World) -> bool {
+        let start = self.player.entity.get_absolute_position();
+        let items = self.items_in_view();
+        match &self.run {
+            Some(run) if run.direction == (right, up) => {
+                let hurt = self.player.stats.hp < run.hp;
+                let new_item = items.iter().any(|item| !run.seen_items.contains(item));
+                if hurt || new_item || self.creature_in_view() {
+                    self.run = None;
+                    return false;
+                }
+            }
+            _ => {
+                let (left_blocked, right_blocked) = self.sides_blocked(&start, (right, up), world);
+                self.run = Some(Run {
+                    direction: (right, up),
+                    terrain: world.get_tile(&start).copied(),
+                    seen_items: items,
+                    hp: self.player.stats.hp,
+                    in_corridor: left_blocked && right_blocked,
+                });
+            }
+        }
+
+        let took_turn = self.perform_player_action(Action::Move(right, up), world);
+        let position = self.player.entity.get_absolute_position();
+        let (left_blocked, right_blocked) = self.sides_blocked(&position, (right, up), world);
+        let terrain = world.get_tile(&position).copied();
+        let run = self
+            .run
+            .as_mut()
+            .expect("The run is started before the step!");
+        let branches = run.in_corridor && !(left_blocked && right_blocked);
+        run.in_corridor = left_blocked && right_blocked;
+        // Bumping into something, fighting or talking stops the run too.
+        let moved = position.local != start.local || position.chunk != start.chunk;
+        if !moved || terrain != run.terrain || branches || self.talking_to.is_some() {
+            self.run = None;
+        }
+        took_turn
+    }
+
+    /// Are the tiles to the left and to the right of the direction blocked?
+    fn sides_blocked(
+        &self,
+        position: &AbsolutePosition,
+        (right, up): (i8, i8),
+        world: &World,
+    ) -> (bool, bool) {
+        let blocked = |side: (i8, i8)| {
+            let side = position.add_to_local((side.0.into(), side.1.into()));
+            !world.is_walkable(&side)
+                || self
+                    .get_entity_at_pos(&side)
+                    .is_some_and(|entity| Entity::is_blocking(entity).unwrap())
+        };
+        (blocked((-up, right)), blocked((up, -right)))
+    }
+
+    /// The positions of the items the player sees.
+    fn items_in_view(&self) -> HashSet<(i32, i32)> {
+        self.entities
+            .iter()
+            .filter(|entity| entity.item.is_some() || entity.tile == TileType::Chest)
+            .map(|entity| entity.get_absolute_position().get_absolute_position_i32())
+            .filter(|position| self.fov.is_visible(*position))
+            .collect()
+    }
+
+    /// Does the player see any creature?
+    fn creature_in_view(&self) -> bool {
+        self.entities.iter().any(|entity| {
+            entity.stats.is_some()
+                && self
+                    .fov
+                    .is_visible(entity.get_absolute_position().get_absolute_position_i32())
+        })
+    }
+
+    /// Moves the player towards the destination, colliding with whatever is there.
+    /// Bumping into a creature attacks it instead.
+    /// Returns whether the player used the turn.
+    fn move_player(&mut self, world: &mut World) -> bool {
+        let (right, up) = self.player.destination.as_tuple();
+        self.player.facing = (right, up);
+        let future_pos = self.player.calc_future_pos();
+        // There is no squeezing diagonally between two walls.
+        if right != 0 && up != 0 {
+            let position = self.player.entity.get_absolute_position();
+            let blocked = [(right, 0), (0, up)].iter().all(|side| {
+                let side = position.add_to_local(*side);
+                !world.is_walkable(&side)
+                    || self
+                        .get_entity_at_pos(&side)
+                        .is_some_and(|entity| Entity::is_blocking(entity).unwrap())
+            });
+            if blocked {
+                self.events
+                    .message(MessageKind::Info, "There is no room to squeeze through.");
+                return false;
+            }
+        }
+        let collider = self
+            .entities
+            .iter()
+            .position(|e| e.pos == future_pos.local && e.chunk_pos == future_pos.chunk);
+
+        let allowed_to_move = match collider {
+            Some(index) if self.entities[index].stats.is_some() => {
+                self.player_attacks(index);
+                return true;
+            }
+            Some(index) if Entity::is_door(&self.entities[index]) => {
+                return self.open_door(index);
+            }
+            Some(index) if self.entities[index].tile == TileType::StoneEngraving => {
+                self.read_engraving(&future_pos);
+                return true;
+            }
+            Some(index) if Entity::npc(self.entities[index].tile).is_some() => {
+                let npc = &self.entities[index];
+                self.events
+                    .message(MessageKind::Info, format!("You talk to the {}.", npc.tile));
+                let home = npc
+                    .home
+                    .clone()
+                    .unwrap_or_else(|| npc.get_absolute_position());
+                self.talking_to = Entity::npc(npc.tile).map(|name| (name, home));
+                return true;
+            }
+            Some(index) if Entity::wood_yield(self.entities[index].tile) > 0 => {
+                self.cut(index);
+                return true;
+            }
+            Some(index) if self.entities[index].tile == TileType::StoneWall => {
+                return self.dig(index, world);
+            }
+            Some(index) => !Entity::is_blocking(&self.entities[index]).unwrap(),
+            None => true,
+        };
+        if allowed_to_move && !world.is_walkable(&future_pos) {
+            if world.get_tile(&future_pos) == Some(&TileType::Lava) {
+                self.events
+                    .message(MessageKind::Warning, "The lava is too hot to walk on.");
+            }
+            return false;
+        }
+
+        if allowed_to_move {
+            self.player
+                .entity
+                .add_to_local_position(self.player.destination.as_tuple());
+            self.events.emit(GameEvent::EntityMoved {
+                tile: self.player.entity.tile,
+                to: self.player.entity.get_absolute_position(),
+            });
+            let player_pos = self.player.entity.get_absolute_position_f32();
+            for entity in self.entities.iter().filter(|e| !e.removed) {
+                if distance(entity.get_absolute_position_f32(), player_pos) < 1.5 {
+                    self.events.emit(GameEvent::PlayerNextTo(entity.tile));
+                }
+            }
+            if let Some(index) = collider {
+                self.collide(index);
+            }
+        }
+        allowed_to_move
+    }
+
+    /// Draws the entities the player sees, lit by the light falling on them.
+    /// The hallucinating player sees the creatures and the items as something else.
+    pub fn draw(&self, tile_atlas: &TileAtlas, light_map: &LightMap) {
         for entity in self.entities.iter() {
-            let entity_pos = entity.get_absolute_position_f32();
-            let dist = distance(player_pos, entity_pos);
-            if dist < self.player.vision_range.into() {
-                let brightness = self.player.calc_brightness(dist);
-                tile_atlas.draw_entity(entity, brightness);
+            let position = entity.get_absolute_position().get_absolute_position_i32();
+            if !self.fov.is_visible(position) || !light_map.is_lit(position) {
+                continue;
+            }
+            let light = light_map.get(position);
+            let hallucinating = self.player.status.has(StatusKind::Hallucination);
+            if hallucinating && (entity.stats.is_some() || entity.item.is_some()) {
+                let (x, y) = position;
+                let index = (x * 7 + y * 13 + self.clock.turn as i32)
+                    .rem_euclid(HALLUCINATIONS.len() as i32);
+                let mut vision = entity.clone();
+                vision.set_tile(HALLUCINATIONS[index as usize]);
+                tile_atlas.draw_entity(&vision, light);
+            } else {
+                tile_atlas.draw_entity(entity, light);
             }
         }
-        tile_atlas.draw_entity(&self.player.entity, 255.into());
+        tile_atlas.draw_entity(&self.player.entity, LightColor::WHITE);
+        for marker in &self.markers {
+            let (x, y) = marker.get_absolute_position_f32();
+            draw_rectangle_lines(x, y, 1., 1., 0.15, YELLOW);
+        }
     }
 
     fn populate_location(
@@ -99,7 +573,12 @@ impl Entities {
     ) {
         let scaled_x = location.x * i32::from(CHUNK_SIZE);
         let scaled_y = location.y * i32::from(CHUNK_SIZE);
-        let entities = generator.generate_entities(scaled_x as f32, scaled_y as f32, terrain);
+        let entities = generator.generate_entities(
+            scaled_x as f32,
+            scaled_y as f32,
+            terrain,
+            self.clock.is_night(),
+        );
 
         for mut entity in entities {
             let entity_pos = AbsolutePosition {
@@ -135,7 +614,12 @@ impl Entities {
         chunk_terrain: &ChunkTerrain,
         generator: &Generator,
     ) {
-        if let Some(entities) = self.entities_store.remove(location) {
+        let stored = self.entities_store.remove(location);
+        self.events.emit(GameEvent::ChunkLoaded {
+            chunk: *location,
+            first_visit: stored.is_none(),
+        });
+        if let Some(entities) = stored {
             self.load_entities(entities);
         } else {
             self.populate_location(*location, chunk_terrain, generator);
@@ -159,15 +643,10 @@ impl Entities {
         self.entities.append(&mut entities);
     }
 
-    fn clean_up(&mut self) {
+    pub(super) fn clean_up(&mut self) {
         self.entities.retain(|e| !e.removed);
     }
 
-    pub fn delete_entity_at_location(&mut self, position: &AbsolutePosition) {
-        self.entities
-            .retain(|e| !(e.chunk_pos == position.chunk && e.pos == position.local));
-    }
-
     pub fn distance_to_closest(
         &self,
         position: &AbsolutePosition,
@@ -200,6 +679,13 @@ impl Entities {
             .find(|e| e.pos == position.local && e.chunk_pos == position.chunk)
     }
 
+    /// All the entities on the tile, by the absolute tile coordinates.
+    pub fn entities_at(&self, position: (i32, i32)) -> impl Iterator<Item = &Entity> {
+        self.entities.iter().filter(move |e| {
+            !e.removed && e.get_absolute_position().get_absolute_position_i32() == position
+        })
+    }
+
     pub fn get_entity_at_pos(&self, position: &AbsolutePosition) -> Option<&Entity> {
         self.entities
             .iter()
@@ -207,11 +693,17 @@ impl Entities {
     }
 
     pub fn create_hut(&mut self, buttom_left: &AbsolutePosition) -> Vec<Entity> {
-        let size = 5;
+        let size = HUT_SIZE;
 
         let blueprint = [
-            1, 1, 1, 1, 1, 1, 0, 3, 0, 1, 1, 0, 0, 0, 1, 1, 0, 0, 0, 1, 1, 2, 0, 2, 1,
+            1, 1, 1, 1, 1, 1, 0, 3, 0, 1, 5, 0, 6, 0, 5, 1, 0, 0, 0, 1, 1, 2, 4, 2, 1,
         ];
+        // Some of the huts are locked and need a key to get in.
+        let door = if self.rng.chance(30) {
+            TileType::LockedDoor
+        } else {
+            TileType::Door
+        };
 
         let res: Vec<Entity> = Vec::with_capacity(blueprint.len());
         for height in 0..size {
@@ -221,7 +713,6 @@ impl Entities {
                 if let Some(entity) = self.get_mut_entity_at_pos(&absolute_position) {
                     entity.removed = true;
                 }
-                //self.add_debug_entity(absolute_position.clone());
 
                 let mut base_entity = Entity::default();
 
@@ -239,6 +730,17 @@ impl Entities {
                     3 => {
                         base_entity.set_tile(TileType::Chest);
                     }
+                    4 => {
+                        base_entity.set_tile(door);
+                    }
+                    5 => {
+                        base_entity.set_tile(TileType::WallTorch);
+                    }
+                    // Some of the huts have a cat living in them.
+                    6 => {
+                        base_entity = Entity::new(absolute_position.clone(), TileType::Cat);
+                        base_entity.removed = !self.rng.chance(50);
+                    }
                     _ => {
                         unreachable!();
                     }
@@ -249,9 +751,6 @@ impl Entities {
         }
         res
     }
-    pub fn add_debug_entity(&mut self, pos: AbsolutePosition) {
-        self.entities.push(Entity::new(pos, TileType::Debug));
-    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -260,6 +759,16 @@ pub struct Entity {
     pub pos: LocalPosition,
     pub tile: TileType,
     pub removed: bool,
+    /// Entities that can act in the world have an `Actor`.
+    pub actor: Option<Actor>,
+    /// Creatures that can fight and be killed have `Stats`.
+    pub stats: Option<Stats>,
+    /// Items lying in the world.
+    pub item: Option<Item>,
+    /// The effects on the creature, like the poison from the player's dagger.
+    pub status: StatusEffects,
+    /// Where the NPC lives, it doesn't stray far from it and is known by it.
+    pub home: Option<AbsolutePosition>,
 }
 
 impl Entity {
@@ -269,6 +778,11 @@ impl Entity {
             pos,
             tile,
             removed: false,
+            actor: Self::speed(tile).map(Actor::new),
+            stats: Self::creature(tile).map(|(stats, _)| stats),
+            item: Self::item(tile),
+            status: StatusEffects::default(),
+            home: None,
         }
     }
 
@@ -278,9 +792,21 @@ impl Entity {
             pos: pos.local,
             tile,
             removed: false,
+            actor: Self::speed(tile).map(Actor::new),
+            stats: Self::creature(tile).map(|(stats, _)| stats),
+            item: Self::item(tile),
+            status: StatusEffects::default(),
+            home: Self::npc(tile).map(|_| pos.clone()),
         }
     }
 
+    /// Creates the entity for the item lying in the world.
+    pub fn new_item(pos: AbsolutePosition, item: Item) -> Self {
+        let mut entity = Self::new(pos, item.kind.tile());
+        entity.item = Some(item);
+        entity
+    }
+
     pub fn set_tile(&mut self, tile: TileType) {
         self.tile = tile;
     }
@@ -303,15 +829,70 @@ impl Entity {
         self.set_local_position(pos.local);
     }
 
-    pub fn collide(&mut self, player: &mut Player) {
-        if Entity::is_pickup(self).unwrap() {
-            self.removed = true;
-            if self.tile == TileType::Coin {
-                player.score += 1;
-            } else if self.tile == TileType::Chest {
-                player.score += 5;
+    /// Writes the entity as `chunk_x,chunk_y,x,y,tile,hp,item,status,home`,
+    /// with `-` where there are no hit points, items, status effects or home.
+    pub fn save_string(&self) -> String {
+        let hp = self
+            .stats
+            .map_or("-".to_string(), |stats| stats.hp.to_string());
+        let item = self.item.map_or("-".to_string(), |item| {
+            format!("{:?}:{}", item.kind, item.count)
+        });
+        let status = self
+            .status
+            .iter()
+            .map(|effect| format!("{:?}:{}:{}", effect.kind, effect.turns, effect.potency))
+            .collect::<Vec<_>>()
+            .join(";");
+        let home = self.home.as_ref().map_or("-".to_string(), |home| {
+            let (x, y) = home.get_absolute_position_i32();
+            format!("{}:{}", x, y)
+        });
+        format!(
+            "{},{:?},{},{},{},{}",
+            format_position(&self.get_absolute_position()),
+            self.tile,
+            hp,
+            item,
+            if status.is_empty() { "-" } else { &status },
+            home
+        )
+    }
+
+    /// Reads the entity written by `save_string`.
+    pub fn from_save_string(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.split(',').collect();
+        if parts.len() != 9 {
+            return None;
+        }
+        let position = parse_position(&parts[..4].join(","))?;
+        let mut entity = Self::new(position, parse_variant(&TileType::ALL, parts[4])?);
+        if let (Some(stats), Ok(hp)) = (entity.stats.as_mut(), parts[5].parse()) {
+            stats.hp = hp;
+        }
+        if let Some((kind, count)) = parts[6].split_once(':') {
+            let kind = parse_variant(&ItemKind::ALL, kind)?;
+            entity.item = Some(Item::new(kind, count.parse().ok()?));
+        }
+        for value in parts[7].split(';') {
+            if value == "-" {
+                continue;
             }
+            let mut fields = value.split(':');
+            let kind = parse_variant(&StatusKind::ALL, fields.next()?)?;
+            let mut effect = StatusEffect::new(kind, fields.next()?.parse().ok()?);
+            effect.potency = fields.next()?.parse().ok()?;
+            entity.status.add(effect);
         }
+        if let Some((x, y)) = parts[8].split_once(':') {
+            let home = (x.parse().ok()?, y.parse().ok()?);
+            entity.home = Some(AbsolutePosition::from_absolute_i32(home));
+        }
+        Some(entity)
+    }
+
+    pub fn is_at(&self, position: &AbsolutePosition) -> bool {
+        self.pos == position.local && self.chunk_pos == position.chunk
     }
 
     pub fn get_absolute_position(&self) -> AbsolutePosition {
@@ -333,6 +914,29 @@ impl Entity {
         )
     }
 
+    /// The things worth knowing about the entity: its health,
+    /// whether it's in the way and what it's worth.
+    pub fn properties(&self) -> Vec<String> {
+        let mut properties = Vec::new();
+        if let Some(stats) = &self.stats {
+            properties.push(format!("{}/{} HP", stats.hp, stats.max_hp));
+        }
+        for effect in self.status.iter() {
+            properties.push(effect.kind.to_string());
+        }
+        if Self::is_blocking(self) == Some(true) {
+            properties.push("blocks the way".to_string());
+        }
+        if let Some(item) = &self.item {
+            properties.push(item.to_string());
+            let value = item.kind.value() * i64::from(item.count);
+            if value > 0 {
+                properties.push(format!("worth {}", value));
+            }
+        }
+        properties
+    }
+
     pub const fn is_blocking(entity: &Entity) -> Option<bool> {
         match entity.tile {
             TileType::Debug => Some(false),
@@ -358,14 +962,106 @@ impl Entity {
             TileType::TreeStomp => Some(true),
             TileType::VioletFlower => Some(false),
             TileType::MushroomRed => Some(false),
+            TileType::Rat => Some(true),
+            TileType::Slime => Some(true),
+            TileType::Bag => Some(false),
+            TileType::OpenDoor => Some(false),
+            TileType::LockedDoor => Some(true),
+            TileType::WallTorch => Some(true),
+            TileType::Lava => Some(false),
+            TileType::Ghost => Some(true),
+            TileType::MoonFlower => Some(false),
+            TileType::Puddle => Some(false),
         }
     }
 
-    pub const fn is_pickup(entity: &Entity) -> Option<bool> {
-        match entity.tile {
-            TileType::Chest => Some(true),
-            TileType::Coin => Some(true),
-            _ => Some(false),
+    /// Walls and closed doors can't be seen through.
+    pub const fn is_blocking_sight(entity: &Entity) -> bool {
+        matches!(
+            entity.tile,
+            TileType::WoodenWall
+                | TileType::StoneWall
+                | TileType::WallTorch
+                | TileType::Door
+                | TileType::LockedDoor
+        )
+    }
+
+    /// Closed doors open when bumped into.
+    pub const fn is_door(entity: &Entity) -> bool {
+        matches!(entity.tile, TileType::Door | TileType::LockedDoor)
+    }
+
+    /// The stats and the speed of the creatures.
+    pub const fn creature(tile: TileType) -> Option<(Stats, i32)> {
+        match tile {
+            TileType::Rat => Some((Stats::new(4, 2, 0), 150)),
+            TileType::Slime => Some((Stats::new(10, 4, 1), 50)),
+            TileType::Ghost => Some((Stats::new(6, 4, 0), 100)),
+            _ => None,
+        }
+    }
+
+    /// How much wood cutting the entity with this tile gives.
+    pub const fn wood_yield(tile: TileType) -> u32 {
+        match tile {
+            TileType::Bush => 1,
+            TileType::TreeStomp => 3,
+            TileType::WoodenWall => WALL_COST,
+            _ => 0,
+        }
+    }
+
+    /// The name of the friendly creature, its dialogue tree is found by it.
+    pub const fn npc(tile: TileType) -> Option<&'static str> {
+        match tile {
+            TileType::Cat => Some("cat"),
+            _ => None,
+        }
+    }
+
+    /// How fast the entity acts, if it acts at all.
+    pub(super) const fn speed(tile: TileType) -> Option<i32> {
+        match Self::creature(tile) {
+            Some((_, speed)) => Some(speed),
+            None if Self::npc(tile).is_some() => Some(NORMAL_SPEED),
+            None => None,
+        }
+    }
+
+    /// The status effect the creature's hits may leave on the player.
+    pub const fn inflicts(tile: TileType) -> Option<StatusEffect> {
+        match tile {
+            TileType::Slime => Some(StatusEffect::new(StatusKind::Poison, 5)),
+            TileType::Ghost => Some(StatusEffect::new(StatusKind::Confusion, 6)),
+            _ => None,
+        }
+    }
+
+    /// Creatures and flowers that only live at night.
+    pub const fn is_nocturnal(tile: TileType) -> bool {
+        matches!(tile, TileType::Ghost | TileType::MoonFlower)
+    }
+
+    /// The percent chance for the creature to drop a coin on death.
+    pub const fn drop_chance(tile: TileType) -> i32 {
+        match tile {
+            TileType::Rat => 30,
+            TileType::Slime => 80,
+            _ => 0,
+        }
+    }
+
+    /// The item that the entity with this tile is.
+    pub const fn item(tile: TileType) -> Option<Item> {
+        match tile {
+            TileType::Coin => Some(Item::new(ItemKind::Coin, 1)),
+            TileType::GrassStones | TileType::SandStones => Some(Item::new(ItemKind::Stone, 1)),
+            TileType::WhiteFlower | TileType::VioletFlower => Some(Item::new(ItemKind::Flower, 1)),
+            TileType::MushroomBrown => Some(Item::new(ItemKind::BrownMushroom, 1)),
+            TileType::MushroomRed => Some(Item::new(ItemKind::RedMushroom, 1)),
+            TileType::MushroomOrange => Some(Item::new(ItemKind::OrangeMushroom, 1)),
+            _ => None,
         }
     }
 }
@@ -375,3 +1071,46 @@ impl std::fmt::Display for Entity {
         write!(f, "Entity: {}", self.tile)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_save_round_trip() {
+        let home = AbsolutePosition::from_absolute_i32((-3, 70));
+        let mut entity = Entity::new(home.add_to_local((1, -1)), TileType::Cat);
+        entity.home = Some(home);
+        let mut poison = StatusEffect::new(StatusKind::Poison, 4);
+        poison.potency = 2;
+        entity.status.add(poison);
+        entity.status.add(StatusEffect::new(StatusKind::Haste, 9));
+        let saved = entity.save_string();
+        let loaded = Entity::from_save_string(&saved).expect("The entity loads!");
+        assert_eq!(loaded.save_string(), saved);
+        assert_eq!(loaded.tile, TileType::Cat);
+        assert_eq!(loaded.get_absolute_position_f32(), (-2., 69.));
+        assert!(loaded.status.has(StatusKind::Haste));
+    }
+
+    #[test]
+    fn creature_and_item_save_round_trip() {
+        let position = AbsolutePosition::from_absolute_i32((5, 5));
+        let mut rat = Entity::new(position.clone(), TileType::Rat);
+        rat.stats.as_mut().expect("Rats have stats!").hp = 1;
+        let loaded = Entity::from_save_string(&rat.save_string()).expect("The rat loads!");
+        assert_eq!(loaded.stats.map(|stats| stats.hp), Some(1));
+        assert!(loaded.home.is_none());
+
+        let coins = Entity::new_item(position, Item::new(ItemKind::Coin, 12));
+        let loaded = Entity::from_save_string(&coins.save_string()).expect("The coins load!");
+        assert_eq!(loaded.item, Some(Item::new(ItemKind::Coin, 12)));
+    }
+
+    #[test]
+    fn from_save_string_rejects_the_broken_entities() {
+        assert!(Entity::from_save_string("0,0,1,1,Rat,4,-,-").is_none());
+        assert!(Entity::from_save_string("0,0,1,1,Dragon,4,-,-,-").is_none());
+        assert!(Entity::from_save_string("0,0,1,1,Rat,4,-,Curse:1:1,-").is_none());
+    }
+}
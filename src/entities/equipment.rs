@@ -0,0 +1,79 @@
+use crate::entities::items::ItemKind;
+
+/// The places on the player where the items can be equipped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+    Light,
+    Ring,
+}
+
+impl EquipmentSlot {
+    pub const ALL: [Self; 4] = [Self::Weapon, Self::Armor, Self::Light, Self::Ring];
+
+    const fn index(self) -> usize {
+        match self {
+            Self::Weapon => 0,
+            Self::Armor => 1,
+            Self::Light => 2,
+            Self::Ring => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for EquipmentSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// How much an item changes the stats of the player wearing it.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct StatBonus {
+    pub attack: i32,
+    pub defense: i32,
+    pub vision: i16,
+}
+
+impl std::ops::Add for StatBonus {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            attack: self.attack + other.attack,
+            defense: self.defense + other.defense,
+            vision: self.vision + other.vision,
+        }
+    }
+}
+
+/// The items the player has equipped, one per slot.
+#[derive(Debug, Clone, Default)]
+pub struct Equipment {
+    slots: [Option<ItemKind>; 4],
+}
+
+impl Equipment {
+    pub const fn get(&self, slot: EquipmentSlot) -> Option<ItemKind> {
+        self.slots[slot.index()]
+    }
+
+    /// Equips the item into its slot.
+    /// Returns the item that was in the slot before, if any.
+    pub fn equip(&mut self, slot: EquipmentSlot, kind: ItemKind) -> Option<ItemKind> {
+        self.slots[slot.index()].replace(kind)
+    }
+
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> Option<ItemKind> {
+        self.slots[slot.index()].take()
+    }
+
+    /// The sum of the bonuses from all the equipped items.
+    pub fn bonus(&self) -> StatBonus {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|kind| kind.bonus())
+            .fold(StatBonus::default(), |sum, bonus| sum + bonus)
+    }
+}
@@ -0,0 +1,174 @@
+use crate::entities::entities::Entities;
+use crate::entities::items::ItemKind;
+use crate::entities::status::{StatusEffect, StatusKind};
+use crate::message_log::MessageKind;
+
+/// How much food the player can have in the stomach.
+pub const MAX_FOOD: i32 = 1000;
+/// Below this the player gets hungry.
+const HUNGRY: i32 = 300;
+/// Below this the player is weak from the hunger.
+const WEAK: i32 = 100;
+/// How many turns pass between the starvation damage.
+const STARVATION_INTERVAL: i32 = 10;
+
+/// How hungry the player feels.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub enum HungerState {
+    Fed,
+    Hungry,
+    Weak,
+    Starving,
+}
+
+impl std::fmt::Display for HungerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The food in the player's stomach, a bit of it is used up every turn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Hunger {
+    pub food: i32,
+    /// How many turns the player has been starving for.
+    pub starving_turns: i32,
+}
+
+impl Hunger {
+    pub const fn state(&self) -> HungerState {
+        if self.food <= 0 {
+            HungerState::Starving
+        } else if self.food < WEAK {
+            HungerState::Weak
+        } else if self.food < HUNGRY {
+            HungerState::Hungry
+        } else {
+            HungerState::Fed
+        }
+    }
+
+    /// Uses up the food for one turn.
+    /// Returns whether the starvation hurts the player this turn.
+    pub fn tick(&mut self) -> bool {
+        self.food = (self.food - 1).max(0);
+        if self.food > 0 {
+            self.starving_turns = 0;
+            return false;
+        }
+        self.starving_turns += 1;
+        self.starving_turns % STARVATION_INTERVAL == 0
+    }
+
+    pub fn eat(&mut self, nourishment: i32) {
+        self.food = (self.food + nourishment).min(MAX_FOOD);
+    }
+
+    /// How full the stomach is, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        self.food as f32 / MAX_FOOD as f32
+    }
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self {
+            food: MAX_FOOD,
+            starving_turns: 0,
+        }
+    }
+}
+
+impl Entities {
+    /// Fills the stomach with the food, some of the mushrooms have side effects.
+    pub(super) fn eat(&mut self, kind: ItemKind) {
+        self.player.hunger.eat(kind.nourishment());
+        self.events
+            .message(MessageKind::Info, format!("You eat the {}.", kind));
+        let status = &mut self.player.status;
+        match kind {
+            ItemKind::BrownMushroom => {
+                status.add(StatusEffect::new(StatusKind::Regeneration, 5));
+            }
+            ItemKind::RedMushroom => {
+                self.events
+                    .message(MessageKind::Warning, "You feel sick, it was poisonous!");
+                let mut poison = StatusEffect::new(StatusKind::Poison, 4);
+                poison.potency = 2;
+                status.add(poison);
+            }
+            ItemKind::OrangeMushroom => {
+                self.events.message(
+                    MessageKind::Discovery,
+                    "The colours start to swirl around you.",
+                );
+                status.add(StatusEffect::new(StatusKind::Hallucination, 40));
+                status.add(StatusEffect::new(StatusKind::Confusion, 10));
+            }
+            _ => {}
+        }
+    }
+
+    /// Uses up the food every turn, with nothing left to eat the player starves.
+    pub(super) fn digest(&mut self) {
+        let state = self.player.hunger.state();
+        let starving = self.player.hunger.tick();
+        let new_state = self.player.hunger.state();
+        if new_state != state {
+            let message = match new_state {
+                HungerState::Fed => None,
+                HungerState::Hungry => Some("You are getting hungry."),
+                HungerState::Weak => Some("You feel weak from the hunger."),
+                HungerState::Starving => Some("You are starving!"),
+            };
+            if let Some(message) = message {
+                self.events.message(MessageKind::Warning, message);
+            }
+        }
+        if starving && self.player.stats.take_damage(1) {
+            self.player_dies("Starved to death".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_follows_the_food() {
+        let hunger = |food| Hunger {
+            food,
+            starving_turns: 0,
+        };
+        assert_eq!(hunger(MAX_FOOD).state(), HungerState::Fed);
+        assert_eq!(hunger(HUNGRY - 1).state(), HungerState::Hungry);
+        assert_eq!(hunger(WEAK - 1).state(), HungerState::Weak);
+        assert_eq!(hunger(0).state(), HungerState::Starving);
+    }
+
+    #[test]
+    fn starvation_hurts_every_interval() {
+        let mut hunger = Hunger {
+            food: 1,
+            starving_turns: 0,
+        };
+        let hurting_turns = (0..STARVATION_INTERVAL * 2)
+            .filter(|_| hunger.tick())
+            .count();
+        assert_eq!(hurting_turns, 2);
+        assert_eq!(hunger.food, 0);
+    }
+
+    #[test]
+    fn eating_stops_the_starvation() {
+        let mut hunger = Hunger {
+            food: 0,
+            starving_turns: 5,
+        };
+        hunger.eat(MAX_FOOD * 2);
+        assert_eq!(hunger.food, MAX_FOOD);
+        assert!(!hunger.tick());
+        assert_eq!(hunger.starving_turns, 0);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::entities::entities::{Entities, Entity};
+use crate::entities::equipment::EquipmentSlot;
+use crate::entities::items::{Item, ItemKind};
+use crate::entities::status::{StatusEffect, StatusKind};
+use crate::events::GameEvent;
+use crate::message_log::MessageKind;
+use crate::tile_types::TileType;
+
+/// How many different stacks of items fit into the inventory.
+pub const MAX_SLOTS: usize = 12;
+/// How much weight the player is able to carry.
+pub const MAX_WEIGHT: u32 = 40;
+
+/// The items the player carries around.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Item> {
+        self.items.get(index)
+    }
+
+    /// The inventory slot with the items of this kind.
+    pub fn position(&self, kind: ItemKind) -> Option<usize> {
+        self.items.iter().position(|item| item.kind == kind)
+    }
+
+    /// How many items of this kind there are.
+    pub fn count(&self, kind: ItemKind) -> u32 {
+        self.position(kind)
+            .map_or(0, |index| self.items[index].count)
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.items.iter().map(Item::weight).sum()
+    }
+
+    /// Adds as many of the items as it can.
+    /// Returns the items that didn't fit, if there are any.
+    pub fn add(&mut self, mut item: Item) -> Option<Item> {
+        let free_weight = MAX_WEIGHT.saturating_sub(self.weight());
+        let fitting = if item.kind.weight() == 0 {
+            item.count
+        } else {
+            item.count.min(free_weight / item.kind.weight())
+        };
+        if fitting == 0 {
+            return Some(item);
+        }
+
+        if let Some(stack) = self.items.iter_mut().find(|i| i.kind == item.kind) {
+            stack.count += fitting;
+        } else if self.items.len() < MAX_SLOTS {
+            self.items.push(Item::new(item.kind, fitting));
+        } else {
+            return Some(item);
+        }
+
+        item.count -= fitting;
+        if item.count > 0 {
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    /// Takes `count` items from the stack at `index`, or less if there aren't enough.
+    pub fn take(&mut self, index: usize, count: u32) -> Option<Item> {
+        let stack = self.items.get_mut(index)?;
+        let taken = Item::new(stack.kind, count.min(stack.count));
+        stack.count -= taken.count;
+        if stack.count == 0 {
+            self.items.remove(index);
+        }
+        Some(taken)
+    }
+}
+
+impl Entities {
+    /// The player stepped on the non blocking entity at `index`.
+    /// Chests are opened and some items are picked up right away.
+    pub(super) fn collide(&mut self, index: usize) {
+        let entity = &self.entities[index];
+        if entity.tile == TileType::Chest {
+            self.open_chest(index);
+        } else if let Some(item) = entity.item {
+            if item.kind.is_auto_pickup() {
+                self.pick_up(index);
+            } else {
+                self.events
+                    .message(MessageKind::Info, format!("You see a {} here.", item));
+            }
+        }
+    }
+
+    /// Opens the chest at `index`, the loot goes to the inventory
+    /// and whatever doesn't fit is left on the ground.
+    fn open_chest(&mut self, index: usize) {
+        let chest = &mut self.entities[index];
+        chest.removed = true;
+        self.events
+            .message(MessageKind::Discovery, "You open the chest.");
+
+        let mut loot = vec![Item::new(ItemKind::Coin, self.rng.range(2, 8) as u32)];
+        if self.rng.chance(40) {
+            loot.push(Item::new(ItemKind::HealingPotion, 1));
+        }
+        if self.rng.chance(15) {
+            loot.push(Item::new(ItemKind::SwiftnessPotion, 1));
+        }
+        if self.rng.chance(30) {
+            loot.push(Item::new(ItemKind::Gem, self.rng.range(1, 3) as u32));
+        }
+
+        if self.rng.chance(35) {
+            let equipment = [
+                ItemKind::Dagger,
+                ItemKind::Pickaxe,
+                ItemKind::Sword,
+                ItemKind::LeatherArmor,
+                ItemKind::Lantern,
+                ItemKind::ProtectionRing,
+            ];
+            let kind = equipment[self.rng.range(0, equipment.len() as i32) as usize];
+            loot.push(Item::new(kind, 1));
+        }
+        if self.rng.chance(25) {
+            loot.push(Item::new(ItemKind::Key, 1));
+        }
+
+        for item in loot {
+            self.events
+                .message(MessageKind::Pickup, format!("You find a {}.", item));
+            // What falls on the ground is only picked up when the player picks it up.
+            if let Some(stashed) = self.stash(item) {
+                self.events.emit(GameEvent::ItemPickedUp(stashed));
+            }
+        }
+    }
+
+    /// Picks up as much as possible from the item entity at `index`.
+    /// Returns whether anything was picked up.
+    fn pick_up(&mut self, index: usize) -> bool {
+        let entity = &mut self.entities[index];
+        let item = match entity.item {
+            Some(item) => item,
+            None => return false,
+        };
+        match self.player.inventory.add(item) {
+            None => {
+                entity.removed = true;
+                self.events
+                    .message(MessageKind::Pickup, format!("You pick up a {}.", item));
+                self.events.emit(GameEvent::ItemPickedUp(item));
+                true
+            }
+            Some(left_over) if left_over == item => {
+                self.events.message(
+                    MessageKind::Warning,
+                    format!("You can't carry the {}.", item),
+                );
+                false
+            }
+            Some(left_over) => {
+                entity.item = Some(left_over);
+                self.events.message(
+                    MessageKind::Pickup,
+                    format!("You pick up some of the {}.", item.kind),
+                );
+                let picked = Item::new(item.kind, item.count - left_over.count);
+                self.events.emit(GameEvent::ItemPickedUp(picked));
+                true
+            }
+        }
+    }
+
+    /// Picks up the item the player is standing on.
+    pub(super) fn pick_up_here(&mut self) -> bool {
+        let position = self.player.entity.get_absolute_position();
+        let index = self.entities.iter().position(|e| {
+            e.item.is_some() && e.pos == position.local && e.chunk_pos == position.chunk
+        });
+        match index {
+            Some(index) => self.pick_up(index),
+            None => {
+                self.events
+                    .message(MessageKind::Info, "There is nothing here to pick up.");
+                false
+            }
+        }
+    }
+
+    /// Uses one of the items from the inventory slot at `index`.
+    pub(super) fn use_item(&mut self, index: usize) -> bool {
+        let kind = match self.player.inventory.get(index) {
+            Some(item) => item.kind,
+            None => return false,
+        };
+        if let Some(slot) = kind.slot() {
+            return self.equip(index, slot);
+        }
+        if kind.nourishment() > 0 {
+            self.player.inventory.take(index, 1);
+            self.eat(kind);
+            return true;
+        }
+        match kind {
+            ItemKind::HealingPotion => {
+                self.player.inventory.take(index, 1);
+                let stats = &mut self.player.stats;
+                stats.hp = (stats.hp + 10).min(stats.max_hp);
+                self.events
+                    .message(MessageKind::Info, "You drink the healing potion.");
+                true
+            }
+            ItemKind::SwiftnessPotion => {
+                self.player.inventory.take(index, 1);
+                self.events
+                    .message(MessageKind::Info, "You drink the potion, you feel quick.");
+                self.player
+                    .status
+                    .add(StatusEffect::new(StatusKind::Haste, 30));
+                true
+            }
+            _ => {
+                self.events
+                    .message(MessageKind::Info, format!("You can't use the {}.", kind));
+                false
+            }
+        }
+    }
+
+    /// Equips the item from the inventory slot at `index`,
+    /// the previously equipped item goes back into the inventory.
+    fn equip(&mut self, index: usize, slot: EquipmentSlot) -> bool {
+        let item = match self.player.inventory.take(index, 1) {
+            Some(item) => item,
+            None => return false,
+        };
+        self.events
+            .message(MessageKind::Info, format!("You equip the {}.", item.kind));
+        if let Some(previous) = self.player.equipment.equip(slot, item.kind) {
+            self.stash(Item::new(previous, 1));
+        }
+        true
+    }
+
+    /// Takes off the item in the equipment `slot`.
+    pub(super) fn unequip(&mut self, slot: EquipmentSlot) -> bool {
+        match self.player.equipment.unequip(slot) {
+            Some(kind) => {
+                self.events
+                    .message(MessageKind::Info, format!("You take off the {}.", kind));
+                self.stash(Item::new(kind, 1));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Puts the item into the inventory, or on the ground if it doesn't fit.
+    /// Returns the part of the item that went into the inventory.
+    pub(super) fn stash(&mut self, item: Item) -> Option<Item> {
+        let left_over = match self.player.inventory.add(item) {
+            Some(left_over) => left_over,
+            None => return Some(item),
+        };
+        self.events.message(
+            MessageKind::Warning,
+            format!("You can't carry the {}, it falls out.", left_over),
+        );
+        let position = self.player.entity.get_absolute_position();
+        self.entities.push(Entity::new_item(position, left_over));
+        (left_over.count < item.count).then(|| Item::new(item.kind, item.count - left_over.count))
+    }
+
+    /// Drops the whole stack at the inventory slot `index` where the player stands.
+    pub(super) fn drop_item(&mut self, index: usize) -> bool {
+        let position = self.player.entity.get_absolute_position();
+        if self.get_entity_at_pos(&position).is_some() {
+            self.events
+                .message(MessageKind::Info, "There is no room to drop anything here.");
+            return false;
+        }
+        match self.player.inventory.take(index, u32::MAX) {
+            Some(item) => {
+                self.events
+                    .message(MessageKind::Info, format!("You drop the {}.", item));
+                self.events.emit(GameEvent::ItemDropped(item));
+                self.entities.push(Entity::new_item(position, item));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes the items out of the inventory, wherever they are.
+    pub(super) fn take_items(&mut self, item: Item) {
+        if let Some(index) = self.player.inventory.position(item.kind) {
+            self.player.inventory.take(index, item.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_stacks_the_same_kind() {
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.add(Item::new(ItemKind::Stone, 2)), None);
+        assert_eq!(inventory.add(Item::new(ItemKind::Stone, 3)), None);
+        assert_eq!(inventory.items(), &[Item::new(ItemKind::Stone, 5)]);
+    }
+
+    #[test]
+    fn add_returns_what_is_too_heavy() {
+        let mut inventory = Inventory::default();
+        let left_over = inventory.add(Item::new(ItemKind::Wood, 25));
+        assert_eq!(left_over, Some(Item::new(ItemKind::Wood, 5)));
+        assert_eq!(inventory.weight(), MAX_WEIGHT);
+        // The weightless items still fit.
+        assert_eq!(inventory.add(Item::new(ItemKind::Coin, 100)), None);
+    }
+
+    #[test]
+    fn add_returns_everything_without_a_free_slot() {
+        let mut kinds = ItemKind::ALL.to_vec();
+        kinds.sort_by_key(|kind| kind.weight());
+        let mut inventory = Inventory::default();
+        for kind in &kinds[..MAX_SLOTS] {
+            assert_eq!(inventory.add(Item::new(*kind, 1)), None);
+        }
+        let item = Item::new(kinds[MAX_SLOTS], 1);
+        assert_eq!(inventory.add(item), Some(item));
+    }
+
+    #[test]
+    fn take_removes_the_empty_stack() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::new(ItemKind::Gem, 2));
+        assert_eq!(inventory.take(0, 5), Some(Item::new(ItemKind::Gem, 2)));
+        assert!(inventory.items().is_empty());
+        assert_eq!(inventory.take(0, 1), None);
+    }
+}
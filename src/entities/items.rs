@@ -0,0 +1,207 @@
+use crate::entities::equipment::{EquipmentSlot, StatBonus};
+use crate::entities::status::{StatusEffect, StatusKind};
+use crate::tile_types::TileType;
+
+/// All the kinds of items that can be carried around.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ItemKind {
+    Coin,
+    Gem,
+    HealingPotion,
+    SwiftnessPotion,
+    Dagger,
+    Sword,
+    LeatherArmor,
+    Lantern,
+    ProtectionRing,
+    Key,
+    Pickaxe,
+    Torch,
+    Wood,
+    Stone,
+    StoneBlock,
+    Flower,
+    BrownMushroom,
+    RedMushroom,
+    OrangeMushroom,
+}
+
+impl ItemKind {
+    /// Every item kind, to find them by name when loading the game.
+    pub const ALL: [Self; 19] = [
+        Self::Coin,
+        Self::Gem,
+        Self::HealingPotion,
+        Self::SwiftnessPotion,
+        Self::Dagger,
+        Self::Sword,
+        Self::LeatherArmor,
+        Self::Lantern,
+        Self::ProtectionRing,
+        Self::Key,
+        Self::Pickaxe,
+        Self::Torch,
+        Self::Wood,
+        Self::Stone,
+        Self::StoneBlock,
+        Self::Flower,
+        Self::BrownMushroom,
+        Self::RedMushroom,
+        Self::OrangeMushroom,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Coin => "coin",
+            Self::Gem => "gem",
+            Self::HealingPotion => "healing potion",
+            Self::SwiftnessPotion => "potion of swiftness",
+            Self::Dagger => "dagger",
+            Self::Sword => "sword",
+            Self::LeatherArmor => "leather armor",
+            Self::Lantern => "lantern",
+            Self::ProtectionRing => "ring of protection",
+            Self::Key => "key",
+            Self::Pickaxe => "pickaxe",
+            Self::Torch => "torch",
+            Self::Wood => "wood",
+            Self::Stone => "stone",
+            Self::StoneBlock => "stone block",
+            Self::Flower => "flower",
+            Self::BrownMushroom => "brown mushroom",
+            Self::RedMushroom => "red mushroom",
+            Self::OrangeMushroom => "orange mushroom",
+        }
+    }
+
+    /// How heavy a single item of this kind is.
+    pub const fn weight(self) -> u32 {
+        match self {
+            Self::Coin => 0,
+            Self::Gem => 1,
+            Self::HealingPotion | Self::SwiftnessPotion => 2,
+            Self::Dagger => 3,
+            Self::Sword => 8,
+            Self::LeatherArmor => 10,
+            Self::Lantern => 4,
+            Self::ProtectionRing => 0,
+            Self::Key => 1,
+            Self::Pickaxe => 6,
+            Self::Torch => 2,
+            Self::Wood => 2,
+            Self::Stone => 2,
+            Self::StoneBlock => 5,
+            Self::Flower => 0,
+            Self::BrownMushroom | Self::RedMushroom | Self::OrangeMushroom => 1,
+        }
+    }
+
+    /// How much a single item of this kind adds to the score.
+    pub const fn value(self) -> i64 {
+        match self {
+            Self::Coin => 1,
+            Self::Gem => 5,
+            Self::ProtectionRing => 10,
+            _ => 0,
+        }
+    }
+
+    /// Is the item picked up just by walking over it?
+    pub const fn is_auto_pickup(self) -> bool {
+        matches!(self, Self::Coin | Self::Gem)
+    }
+
+    /// How the item looks when it lies in the world.
+    pub const fn tile(self) -> TileType {
+        match self {
+            Self::Coin => TileType::Coin,
+            Self::Stone => TileType::GrassStones,
+            Self::Flower => TileType::WhiteFlower,
+            Self::BrownMushroom => TileType::MushroomBrown,
+            Self::RedMushroom => TileType::MushroomRed,
+            Self::OrangeMushroom => TileType::MushroomOrange,
+            _ => TileType::Bag,
+        }
+    }
+
+    /// How much food eating the item gives, nothing for the inedible items.
+    pub const fn nourishment(self) -> i32 {
+        match self {
+            Self::BrownMushroom => 250,
+            Self::RedMushroom => 80,
+            Self::OrangeMushroom => 120,
+            _ => 0,
+        }
+    }
+
+    /// The equipment slot the item goes to, if it can be equipped.
+    pub const fn slot(self) -> Option<EquipmentSlot> {
+        match self {
+            Self::Dagger | Self::Sword | Self::Pickaxe => Some(EquipmentSlot::Weapon),
+            Self::LeatherArmor => Some(EquipmentSlot::Armor),
+            Self::Lantern | Self::Torch => Some(EquipmentSlot::Light),
+            Self::ProtectionRing => Some(EquipmentSlot::Ring),
+            _ => None,
+        }
+    }
+
+    /// How the item changes the stats when equipped.
+    pub const fn bonus(self) -> StatBonus {
+        let (attack, defense, vision) = match self {
+            Self::Dagger => (2, 0, 0),
+            Self::Pickaxe => (1, 0, 0),
+            Self::Sword => (4, 0, 0),
+            Self::LeatherArmor => (0, 2, 0),
+            Self::Lantern => (0, 0, 6),
+            Self::Torch => (0, 0, 3),
+            Self::ProtectionRing => (0, 1, 0),
+            _ => (0, 0, 0),
+        };
+        StatBonus {
+            attack,
+            defense,
+            vision,
+        }
+    }
+
+    /// The status effect the weapon's hits may leave on the creatures.
+    pub const fn inflicts(self) -> Option<StatusEffect> {
+        match self {
+            Self::Dagger => Some(StatusEffect::new(StatusKind::Poison, 4)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A stack of the items of the same kind.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub count: u32,
+}
+
+impl Item {
+    pub const fn new(kind: ItemKind, count: u32) -> Self {
+        Self { kind, count }
+    }
+
+    pub const fn weight(&self) -> u32 {
+        self.kind.weight() * self.count
+    }
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.count > 1 {
+            write!(f, "{} x{}", self.kind, self.count)
+        } else {
+            write!(f, "{}", self.kind)
+        }
+    }
+}
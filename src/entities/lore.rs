@@ -0,0 +1,162 @@
+use crate::coords::AbsolutePosition;
+use crate::entities::entities::{Entities, HUT_SIZE};
+use crate::entities::quests::ENGRAVING_GIVER;
+use crate::message_log::MessageKind;
+use crate::rng::Rng;
+use crate::tile_types::TileType;
+
+/// Where the writings of the engraved stones are loaded from.
+pub const LORE_PATH: &str = "assets/lore.txt";
+/// The percent of the engraved stones that tell of the quests.
+const ENGRAVING_QUEST_CHANCE: i32 = 30;
+/// The percent of the engraved stones, including the quest ones, that point to the treasure.
+const ENGRAVING_HINT_CHANCE: i32 = 60;
+
+/// The writings the engraved stones are carved with.
+#[derive(Debug, Clone, Default)]
+pub struct Lore {
+    /// The stories about the world.
+    pub stories: Vec<String>,
+    /// The templates for the hints about the treasure,
+    /// with the `{direction}` and the `{distance}` to fill in.
+    pub hints: Vec<String>,
+}
+
+impl Lore {
+    /// Parses the `[story]` and the `[hint]` sections, one writing per line.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lore = Self::default();
+        let mut section = None;
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line {
+                "[story]" => section = Some(&mut lore.stories),
+                "[hint]" => section = Some(&mut lore.hints),
+                _ => section.as_mut()?.push(line.to_string()),
+            }
+        }
+        (!lore.stories.is_empty()).then_some(lore)
+    }
+
+    /// Fills in the hint template pointing `delta` steps away.
+    pub fn hint(template: &str, delta: (i32, i32)) -> String {
+        let (x, y) = delta;
+        let distance = (f64::from(x).hypot(f64::from(y)) / 5.).round() as i32 * 5;
+        template
+            .replace("{direction}", compass_direction(x, y))
+            .replace("{distance}", distance.to_string().as_str())
+    }
+}
+
+/// The compass direction `delta` points to, the y axis points north.
+fn compass_direction(x: i32, y: i32) -> &'static str {
+    const DIRECTIONS: [&str; 8] = [
+        "east",
+        "north-east",
+        "north",
+        "north-west",
+        "west",
+        "south-west",
+        "south",
+        "south-east",
+    ];
+    let angle = f64::from(y)
+        .atan2(f64::from(x))
+        .to_degrees()
+        .rem_euclid(360.);
+    DIRECTIONS[((angle + 22.5) / 45.) as usize % 8]
+}
+
+impl Entities {
+    /// Reads the engraved stone the player sees at `position`, if there is one there.
+    /// Returns whether there was an engraving to read.
+    pub fn examine_engraving(&mut self, position: &AbsolutePosition) -> bool {
+        let is_engraving = self
+            .get_entity_at_pos(position)
+            .is_some_and(|e| e.tile == TileType::StoneEngraving);
+        if !is_engraving || !self.fov.is_visible(position.get_absolute_position_i32()) {
+            return false;
+        }
+        self.read_engraving(position);
+        true
+    }
+
+    /// Reads the engraved stone at `position`. The stone always says the same,
+    /// some of them tell of the quests, some point to the treasure
+    /// and the rest tell the stories of the world.
+    pub(super) fn read_engraving(&mut self, position: &AbsolutePosition) {
+        let mut rng = self.engraving_rng(position);
+        let quests: Vec<String> = self
+            .quest_book
+            .given_by(ENGRAVING_GIVER)
+            .map(|quest| quest.id.clone())
+            .collect();
+        let roll = rng.range(0, 100);
+        if roll < ENGRAVING_QUEST_CHANCE && !quests.is_empty() {
+            let id = &quests[rng.range(0, quests.len() as i32) as usize];
+            self.events.message(
+                MessageKind::Discovery,
+                "You read the engraving on the stone.",
+            );
+            self.start_quest(id);
+            return;
+        }
+
+        let (x, y) = position.get_absolute_position_i32();
+        let nearest_chest = self
+            .entities
+            .iter()
+            .filter(|e| e.tile == TileType::Chest && !e.removed)
+            .map(|e| {
+                let (chest_x, chest_y) = e.get_absolute_position().get_absolute_position_i32();
+                (chest_x - x, chest_y - y)
+            })
+            // The chest in the engraving's own hut is no secret.
+            .filter(|(dx, dy)| dx.abs().max(dy.abs()) >= i32::from(HUT_SIZE))
+            .min_by_key(|(dx, dy)| dx * dx + dy * dy);
+        let text = match nearest_chest {
+            Some(delta) if roll < ENGRAVING_HINT_CHANCE && !self.lore.hints.is_empty() => {
+                let hints = &self.lore.hints;
+                Lore::hint(&hints[rng.range(0, hints.len() as i32) as usize], delta)
+            }
+            _ => {
+                let stories = &self.lore.stories;
+                stories[rng.range(0, stories.len() as i32) as usize].clone()
+            }
+        };
+        self.events.message(
+            MessageKind::Discovery,
+            format!("The engraving reads: \"{}\"", text),
+        );
+        if !self.player.lore.contains(&text) {
+            self.player.lore.push(text);
+        }
+    }
+
+    /// The dice for the engraved stone, the same stone always rolls the same way.
+    fn engraving_rng(&self, position: &AbsolutePosition) -> Rng {
+        let (x, y) = position.get_absolute_position_i32();
+        Rng::new(self.seed as u64 ^ ((x as u64) << 32) ^ (y as u32 as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_the_sections() {
+        let lore = Lore::parse("# Lore.\n[story]\nOnce.\n\nTwice.\n[hint]\nLook {direction}.")
+            .expect("The lore is valid!");
+        assert_eq!(lore.stories, vec!["Once.", "Twice."]);
+        assert_eq!(lore.hints, vec!["Look {direction}."]);
+    }
+
+    #[test]
+    fn parse_rejects_the_broken_lore() {
+        assert!(Lore::parse("A line without a section.").is_none());
+        assert!(Lore::parse("[hint]\nOnly hints.").is_none());
+    }
+}
@@ -1,2 +1,16 @@
+pub mod actor;
+pub mod building;
+pub mod combat;
+pub mod crafting;
+pub mod dialogue;
+#[allow(clippy::module_inception)]
 pub mod entities;
+pub mod equipment;
+pub mod hunger;
+pub mod inventory;
+pub mod items;
+pub mod lore;
 pub mod player;
+pub mod quests;
+pub mod status;
+pub mod turns;
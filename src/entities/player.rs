@@ -1,25 +1,185 @@
 use crate::coords::{get_checked_position, AbsolutePosition, LocalPosition};
+use crate::entities::actor::Actor;
+use crate::entities::combat::Stats;
+use crate::entities::crafting::Recipe;
+use crate::entities::dialogue::DialogueEffect;
 use crate::entities::entities::Entity;
-use crate::graphics::brightness::Brightness;
+use crate::entities::equipment::{Equipment, EquipmentSlot};
+use crate::entities::hunger::{Hunger, HungerState};
+use crate::entities::inventory::Inventory;
+use crate::entities::items::{Item, ItemKind};
+use crate::entities::quests::{Journal, Quest};
+use crate::entities::status::{StatusEffect, StatusEffects, StatusKind};
+use crate::save::{format_position, parse_list, parse_position, parse_variant, SaveFile};
 use crate::tile_types::TileType;
 
+use std::collections::HashSet;
+
 #[derive(Debug, Clone)]
 pub struct Player {
     pub entity: Entity,
     pub destination: Destination,
+    pub actor: Actor,
+    pub stats: Stats,
+    /// What killed the player, if the player is dead.
+    pub cause_of_death: Option<String>,
+    /// The action the player wants to take on the next update.
+    pub action: Option<Action>,
+    /// The direction of the last step, the player builds in front of them.
+    pub facing: (i16, i16),
+    /// How far the player sees without the equipment, changes with the time of day.
     pub vision_range: i16,
-    pub score: i64,
+    pub inventory: Inventory,
+    pub equipment: Equipment,
+    pub hunger: Hunger,
+    /// The temporary effects on the player.
+    pub status: StatusEffects,
+    /// The tags of the dialogue effects that already happened.
+    pub dialogue_flags: HashSet<String>,
+    pub journal: Journal,
+    /// The writings the player read on the engraved stones.
+    pub lore: Vec<String>,
 }
 
 impl Player {
-    pub fn calc_brightness(&self, distance: f32) -> Brightness {
-        if distance > self.vision_range.into() {
-            Brightness::from(0)
+    /// How far the player sees, with the bonus from the equipped light source
+    /// and whatever the status effects do to the sight.
+    pub fn vision(&self) -> i16 {
+        (self.vision_range + self.equipment.bonus().vision + self.status.vision_bonus()).max(1)
+    }
+
+    /// The player's stats with the bonuses from the equipment,
+    /// the hunger makes the player hit weaker.
+    pub fn combat_stats(&self) -> Stats {
+        let bonus = self.equipment.bonus();
+        let weakness = if self.hunger.state() >= HungerState::Weak {
+            2
         } else {
-            Brightness::from((255. - 255. / (f32::from(self.vision_range + 5) / distance)) as u8)
+            0
+        };
+        Stats {
+            attack: (self.stats.attack + bonus.attack - weakness).max(1),
+            defense: self.stats.defense + bonus.defense,
+            ..self.stats
         }
     }
 
+    pub const fn is_dead(&self) -> bool {
+        self.stats.is_dead()
+    }
+
+    /// Writes down everything about the player that has to survive a restart.
+    pub fn save(&self, file: &mut SaveFile) {
+        let position = self.entity.get_absolute_position();
+        file.push("player_position", format_position(&position));
+        let Stats {
+            hp,
+            max_hp,
+            attack,
+            defense,
+        } = self.stats;
+        file.push(
+            "player_stats",
+            format!("{},{},{},{}", hp, max_hp, attack, defense),
+        );
+        file.push(
+            "player_food",
+            format!("{},{}", self.hunger.food, self.hunger.starving_turns),
+        );
+        for effect in self.status.iter() {
+            file.push(
+                "status",
+                format!("{:?},{},{}", effect.kind, effect.turns, effect.potency),
+            );
+        }
+        for item in self.inventory.items() {
+            file.push("inventory", format!("{:?}:{}", item.kind, item.count));
+        }
+        for slot in EquipmentSlot::ALL {
+            if let Some(kind) = self.equipment.get(slot) {
+                file.push("equipped", format!("{:?}:{:?}", slot, kind));
+            }
+        }
+        for flag in &self.dialogue_flags {
+            file.push("dialogue_flag", flag);
+        }
+        for quest in &self.journal.quests {
+            file.push("quest", quest.save_string());
+        }
+        for reward in &self.journal.rewards {
+            file.push(
+                "quest_reward",
+                format!("{:?}:{}", reward.kind, reward.count),
+            );
+        }
+        for text in &self.lore {
+            file.push("lore", text);
+        }
+    }
+
+    /// Restores the player written down by `save`.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let mut player = Self::default();
+        player
+            .entity
+            .set_position(parse_position(file.get("player_position")?)?);
+        if let [hp, max_hp, attack, defense] = parse_list(file.get("player_stats")?)?.as_slice() {
+            player.stats = Stats {
+                hp: *hp,
+                max_hp: *max_hp,
+                attack: *attack,
+                defense: *defense,
+            };
+        }
+        if let [food, starving_turns] = parse_list(file.get("player_food")?)?.as_slice() {
+            player.hunger.food = *food;
+            player.hunger.starving_turns = *starving_turns;
+        } else {
+            return None;
+        }
+        for value in file.get_all("status") {
+            let mut parts = value.split(',');
+            let kind = parse_variant(&StatusKind::ALL, parts.next()?)?;
+            let mut effect = StatusEffect::new(kind, parts.next()?.parse().ok()?);
+            effect.potency = parts.next()?.parse().ok()?;
+            player.status.add(effect);
+        }
+        for value in file.get_all("inventory") {
+            let (kind, count) = value.split_once(':')?;
+            let kind = parse_variant(&ItemKind::ALL, kind)?;
+            // The saved inventory always fits, a left over means the file is broken.
+            if player
+                .inventory
+                .add(Item::new(kind, count.parse().ok()?))
+                .is_some()
+            {
+                return None;
+            }
+        }
+        for value in file.get_all("equipped") {
+            let (slot, kind) = value.split_once(':')?;
+            player.equipment.equip(
+                parse_variant(&EquipmentSlot::ALL, slot)?,
+                parse_variant(&ItemKind::ALL, kind)?,
+            );
+        }
+        player.dialogue_flags = file.get_all("dialogue_flag").map(String::from).collect();
+        player.journal.quests = file
+            .get_all("quest")
+            .map(Quest::from_save_string)
+            .collect::<Option<_>>()?;
+        for value in file.get_all("quest_reward") {
+            let (kind, count) = value.split_once(':')?;
+            let kind = parse_variant(&ItemKind::ALL, kind)?;
+            player
+                .journal
+                .rewards
+                .push(Item::new(kind, count.parse().ok()?));
+        }
+        player.lore = file.get_all("lore").map(String::from).collect();
+        Some(player)
+    }
+
     pub fn calc_future_pos(&self) -> AbsolutePosition {
         let LocalPosition { x, y } = self.entity.pos;
         let (dest_x, dest_y) = self.destination.as_tuple();
@@ -40,12 +200,48 @@ impl Default for Player {
         Self {
             entity,
             destination: Destination::default(),
+            actor: Actor::default(),
+            stats: Stats::new(20, 5, 1),
+            cause_of_death: None,
+            action: None,
+            facing: (0, 1),
             vision_range: 16,
-            score: 0,
+            inventory: Inventory::default(),
+            equipment: Equipment::default(),
+            hunger: Hunger::default(),
+            status: StatusEffects::default(),
+            dialogue_flags: HashSet::new(),
+            journal: Journal::default(),
+            lore: Vec::new(),
         }
     }
 }
 
+/// Everything the player can do that takes a turn.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Action {
+    Move(i8, i8),
+    /// Keep moving in the direction until something interesting happens.
+    Run(i8, i8),
+    Wait,
+    PickUp,
+    /// Close an open door next to the player.
+    CloseDoor,
+    /// Build a wall in front of the player.
+    Build,
+    /// Make the item from the recipe out of the carried materials.
+    Craft(Recipe),
+    /// Something the NPC does in the conversation, tagged to only happen once.
+    /// Doesn't take a turn.
+    Dialogue(DialogueEffect, String),
+    /// Use the item from the inventory slot.
+    UseItem(usize),
+    /// Drop the items from the inventory slot.
+    DropItem(usize),
+    /// Put the equipped item back into the inventory.
+    Unequip(EquipmentSlot),
+}
+
 #[derive(Debug, Copy, Clone, Default)]
 pub struct Destination {
     x: i8,
@@ -53,9 +249,10 @@ pub struct Destination {
 }
 
 impl Destination {
+    /// Points the destination one step in the direction, the steps don't add up.
     pub fn set_destination(&mut self, right: i8, up: i8) {
-        self.x += right;
-        self.y += up;
+        self.x = right.signum();
+        self.y = up.signum();
     }
 
     pub fn reset_destination(&mut self) {
@@ -0,0 +1,429 @@
+use crate::coords::{distance, AbsolutePosition};
+use crate::entities::entities::Entities;
+use crate::entities::items::{Item, ItemKind};
+use crate::events::{EventBus, EventListener, GameEvent};
+use crate::message_log::MessageKind;
+use crate::save::{format_position, join_escaped, parse_position, parse_variant, split_escaped};
+use crate::tile_types::TileType;
+
+/// Where the quests are loaded from.
+pub const QUESTS_PATH: &str = "assets/quests.txt";
+/// The giver of the quests written on the engraved stones.
+pub const ENGRAVING_GIVER: &str = "engraving";
+
+/// What has to be done to finish the quest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Objective {
+    /// Pick up this many of the items, the dropped ones don't count.
+    Collect(Item),
+    /// Kill this many of the creatures.
+    Defeat(TileType, u32),
+    /// Go to the place this many steps away from where the quest started.
+    Reach(i16, i16),
+    /// Stand next to an entity with this tile.
+    Visit(TileType),
+}
+
+impl Objective {
+    /// How much progress it takes to finish the objective.
+    pub const fn goal(&self) -> u32 {
+        match self {
+            Self::Collect(item) => item.count,
+            Self::Defeat(_, count) => *count,
+            Self::Reach(..) | Self::Visit(_) => 1,
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.split(':').collect::<Vec<&str>>().as_slice() {
+            ["collect", kind, count] => Some(Self::Collect(Item::new(
+                parse_variant(&ItemKind::ALL, kind)?,
+                count.parse().ok()?,
+            ))),
+            ["defeat", tile, count] => Some(Self::Defeat(
+                parse_variant(&TileType::ALL, tile)?,
+                count.parse().ok()?,
+            )),
+            ["reach", offset] => {
+                let (x, y) = offset.split_once(',')?;
+                Some(Self::Reach(x.parse().ok()?, y.parse().ok()?))
+            }
+            ["visit", tile] => Some(Self::Visit(parse_variant(&TileType::ALL, tile)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the objective the same way it is written in the quests file.
+impl std::fmt::Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Collect(item) => write!(f, "collect:{:?}:{}", item.kind, item.count),
+            Self::Defeat(tile, count) => write!(f, "defeat:{:?}:{}", tile, count),
+            Self::Reach(x, y) => write!(f, "reach:{},{}", x, y),
+            Self::Visit(tile) => write!(f, "visit:{:?}", tile),
+        }
+    }
+}
+
+/// A quest as it is written in the quests file.
+#[derive(Debug, Clone, Default)]
+pub struct QuestDefinition {
+    pub id: String,
+    pub giver: String,
+    pub title: String,
+    pub description: String,
+    pub objective: Option<Objective>,
+    pub reward: Option<Item>,
+}
+
+/// All the quests there are to give.
+#[derive(Debug, Clone, Default)]
+pub struct QuestBook {
+    definitions: Vec<QuestDefinition>,
+}
+
+impl QuestBook {
+    pub fn get(&self, id: &str) -> Option<&QuestDefinition> {
+        self.definitions.iter().find(|quest| quest.id == id)
+    }
+
+    /// The quests offered by the giver.
+    pub fn given_by<'a>(&'a self, giver: &'a str) -> impl Iterator<Item = &'a QuestDefinition> {
+        self.definitions
+            .iter()
+            .filter(move |quest| quest.giver == giver)
+    }
+
+    /// Parses the quests. Every quest starts with the `[id]` line followed by
+    /// the `giver=`, `title=`, `description=`, `objective=` and `reward=` lines.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut book = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(id) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                book.definitions.push(QuestDefinition {
+                    id: id.to_string(),
+                    ..QuestDefinition::default()
+                });
+                continue;
+            }
+            let quest = book.definitions.last_mut()?;
+            match line.split_once('=')? {
+                ("giver", giver) => quest.giver = giver.to_string(),
+                ("title", title) => quest.title = title.to_string(),
+                ("description", description) => quest.description = description.to_string(),
+                ("objective", objective) => quest.objective = Some(Objective::parse(objective)?),
+                ("reward", reward) => {
+                    let (kind, count) = reward.split_once(':')?;
+                    quest.reward = Some(Item::new(
+                        parse_variant(&ItemKind::ALL, kind)?,
+                        count.parse().ok()?,
+                    ));
+                }
+                _ => return None,
+            }
+        }
+        // Every quest needs something to do and something to win.
+        book.definitions
+            .iter()
+            .all(|quest| quest.objective.is_some() && quest.reward.is_some())
+            .then_some(book)
+    }
+}
+
+/// A quest the player took on.
+#[derive(Debug, Clone)]
+pub struct Quest {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub objective: Objective,
+    pub reward: Item,
+    pub progress: u32,
+    /// The place to reach, for the quests that need one.
+    pub target: Option<AbsolutePosition>,
+    pub completed: bool,
+}
+
+impl Quest {
+    /// Starts the quest from the definition, `origin` is where the player is.
+    pub fn start(definition: &QuestDefinition, origin: &AbsolutePosition) -> Option<Self> {
+        let objective = definition.objective?;
+        let target = match objective {
+            Objective::Reach(x, y) => Some(origin.add_to_local((x, y))),
+            _ => None,
+        };
+        Some(Self {
+            id: definition.id.clone(),
+            title: definition.title.clone(),
+            description: definition.description.clone(),
+            objective,
+            reward: definition.reward?,
+            progress: 0,
+            target,
+            completed: false,
+        })
+    }
+
+    /// The progress as shown to the player.
+    pub fn progress_text(&self) -> String {
+        if self.completed {
+            "done".to_string()
+        } else {
+            format!("{}/{}", self.progress, self.objective.goal())
+        }
+    }
+
+    /// Writes the quest as `id|title|description|objective|reward|progress|target|completed`,
+    /// with `-` when there is no target. The `|` in the texts is escaped with a backslash.
+    pub fn save_string(&self) -> String {
+        let target = self
+            .target
+            .as_ref()
+            .map_or("-".to_string(), format_position);
+        join_escaped(
+            &[
+                self.id.clone(),
+                self.title.clone(),
+                self.description.clone(),
+                self.objective.to_string(),
+                format!("{:?}:{}", self.reward.kind, self.reward.count),
+                self.progress.to_string(),
+                target,
+                self.completed.to_string(),
+            ],
+            '|',
+        )
+    }
+
+    /// Reads the quest written by `save_string`.
+    pub fn from_save_string(value: &str) -> Option<Self> {
+        let parts = split_escaped(value, '|')?;
+        if let [id, title, description, objective, reward, progress, target, completed] =
+            parts.as_slice()
+        {
+            let (kind, count) = reward.split_once(':')?;
+            Some(Self {
+                id: id.to_string(),
+                title: title.to_string(),
+                description: description.to_string(),
+                objective: Objective::parse(objective)?,
+                reward: Item::new(parse_variant(&ItemKind::ALL, kind)?, count.parse().ok()?),
+                progress: progress.parse().ok()?,
+                target: parse_position(target),
+                completed: completed.parse().ok()?,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// The quests the player took on, the finished ones are kept too.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    pub quests: Vec<Quest>,
+    /// The rewards of the finished quests, waiting to be handed to the player.
+    pub rewards: Vec<Item>,
+}
+
+impl Journal {
+    pub fn has(&self, id: &str) -> bool {
+        self.quests.iter().any(|quest| quest.id == id)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Quest> {
+        self.quests.iter().filter(|quest| !quest.completed)
+    }
+
+    fn active_mut(&mut self) -> impl Iterator<Item = &mut Quest> {
+        self.quests.iter_mut().filter(|quest| !quest.completed)
+    }
+
+    /// Finishes the quests that reached their goal and puts the rewards aside.
+    fn complete_finished(&mut self, bus: &mut EventBus) {
+        for quest in self.quests.iter_mut() {
+            if !quest.completed && quest.progress >= quest.objective.goal() {
+                quest.completed = true;
+                self.rewards.push(quest.reward);
+                bus.emit(GameEvent::QuestCompleted {
+                    title: quest.title.clone(),
+                    reward: quest.reward,
+                });
+            }
+        }
+    }
+}
+
+/// Follows the progress of the quests: the killed creatures, the picked up items
+/// and the places reached or visited.
+impl EventListener for Journal {
+    fn on_event(&mut self, event: &GameEvent, bus: &mut EventBus) {
+        if let GameEvent::EntityMoved {
+            tile: TileType::Pengu,
+            to,
+        } = event
+        {
+            for quest in self.quests.iter_mut() {
+                if !quest.completed && matches!(quest.objective, Objective::Reach(..)) {
+                    let reached = quest.target.as_ref().is_some_and(|target| {
+                        distance(
+                            target.get_absolute_position_f32(),
+                            to.get_absolute_position_f32(),
+                        ) <= 1.
+                    });
+                    quest.progress = u32::from(reached);
+                }
+            }
+        }
+        if let GameEvent::EntityDied { tile } = event {
+            for quest in self.quests.iter_mut() {
+                let counts =
+                    matches!(quest.objective, Objective::Defeat(target, _) if target == *tile);
+                if !quest.completed && counts {
+                    quest.progress += 1;
+                }
+            }
+        }
+        match event {
+            GameEvent::ItemPickedUp(item) => {
+                for quest in self.active_mut() {
+                    if let Objective::Collect(wanted) = quest.objective {
+                        if wanted.kind == item.kind {
+                            quest.progress = (quest.progress + item.count).min(wanted.count);
+                        }
+                    }
+                }
+            }
+            GameEvent::ItemDropped(item) => {
+                for quest in self.active_mut() {
+                    if matches!(quest.objective, Objective::Collect(wanted) if wanted.kind == item.kind)
+                    {
+                        quest.progress = quest.progress.saturating_sub(item.count);
+                    }
+                }
+            }
+            GameEvent::PlayerNextTo(tile) => {
+                for quest in self.active_mut() {
+                    if quest.objective == Objective::Visit(*tile) {
+                        quest.progress = 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.complete_finished(bus);
+    }
+}
+
+impl Entities {
+    /// Puts the quest into the player's journal, unless it's already there.
+    pub(super) fn start_quest(&mut self, id: &str) {
+        if self.player.journal.has(id) {
+            self.events
+                .message(MessageKind::Info, "You already know about this quest.");
+            return;
+        }
+        let origin = self.player.entity.get_absolute_position();
+        match self
+            .quest_book
+            .get(id)
+            .and_then(|definition| Quest::start(definition, &origin))
+        {
+            Some(quest) => {
+                self.events.message(
+                    MessageKind::Discovery,
+                    format!("New quest: {}.", quest.title),
+                );
+                if let Some(target) = &quest.target {
+                    self.markers.push(target.clone());
+                }
+                self.player.journal.quests.push(quest);
+            }
+            None => self
+                .events
+                .message(MessageKind::Warning, format!("There is no quest {}.", id)),
+        }
+    }
+
+    /// Hands the rewards of the finished quests to the player.
+    pub(super) fn hand_out_rewards(&mut self) {
+        for reward in std::mem::take(&mut self.player.journal.rewards) {
+            self.stash(reward);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUESTS: &str = "
+# A comment.
+[rats]
+giver=cat
+title=Rat trouble
+description=Defeat the rats.
+objective=defeat:Rat:3
+reward=Coin:10
+
+[shrine]
+giver=engraving
+title=The shrine
+description=Go north-east.
+objective=reach:30,-4
+reward=Gem:2
+";
+
+    #[test]
+    fn parse_reads_every_quest() {
+        let book = QuestBook::parse(QUESTS).expect("The quests are valid!");
+        let rats = book.get("rats").expect("The rats quest is there!");
+        assert_eq!(rats.title, "Rat trouble");
+        assert_eq!(rats.objective, Some(Objective::Defeat(TileType::Rat, 3)));
+        assert_eq!(rats.reward, Some(Item::new(ItemKind::Coin, 10)));
+        let given: Vec<&str> = book
+            .given_by(ENGRAVING_GIVER)
+            .map(|quest| quest.id.as_str())
+            .collect();
+        assert_eq!(given, vec!["shrine"]);
+        assert_eq!(
+            book.get("shrine").and_then(|quest| quest.objective),
+            Some(Objective::Reach(30, -4))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_the_broken_quests() {
+        assert!(QuestBook::parse("title=No quest yet").is_none());
+        assert!(QuestBook::parse("[rats]\nobjective=defeat:Rat:3").is_none());
+        assert!(QuestBook::parse("[rats]\nobjective=chase:Rat\nreward=Coin:1").is_none());
+        assert!(QuestBook::parse("[rats]\nmood=happy").is_none());
+    }
+
+    #[test]
+    fn quest_save_round_trip_keeps_the_separator() {
+        let book = QuestBook::parse(QUESTS).expect("The quests are valid!");
+        let mut definition = book
+            .get("shrine")
+            .expect("The shrine quest is there!")
+            .clone();
+        definition.title = "North | east \\ far".to_string();
+        let origin = AbsolutePosition::from_absolute_i32((-5, 40));
+        let mut quest = Quest::start(&definition, &origin).expect("The quest can start!");
+        quest.progress = 1;
+        let loaded = Quest::from_save_string(&quest.save_string()).expect("The quest loads!");
+        assert_eq!(loaded.id, quest.id);
+        assert_eq!(loaded.title, quest.title);
+        assert_eq!(loaded.description, quest.description);
+        assert_eq!(loaded.objective, quest.objective);
+        assert_eq!(loaded.reward, quest.reward);
+        assert_eq!(loaded.progress, 1);
+        let target = loaded.target.expect("The target is saved!");
+        assert_eq!(target.get_absolute_position_i32(), (25, 36));
+        assert!(!loaded.completed);
+    }
+}
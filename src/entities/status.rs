@@ -0,0 +1,249 @@
+use macroquad::color::Color;
+
+use crate::entities::actor::NORMAL_SPEED;
+use crate::entities::entities::{Entities, Entity};
+use crate::events::GameEvent;
+use crate::message_log::MessageKind;
+
+/// The kinds of temporary effects that can be put on a creature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StatusKind {
+    /// Hurts every turn, the doses stack up.
+    Poison,
+    /// Heals every turn.
+    Regeneration,
+    /// Makes the creature act more often.
+    Haste,
+    /// Makes the creature stumble in random directions and see less.
+    Confusion,
+    /// Makes the creature see things that aren't there.
+    Hallucination,
+}
+
+impl StatusKind {
+    /// Every status kind, to find them by name when loading the game.
+    pub const ALL: [Self; 5] = [
+        Self::Poison,
+        Self::Regeneration,
+        Self::Haste,
+        Self::Confusion,
+        Self::Hallucination,
+    ];
+
+    /// How many doses of the effect can be piled on top of each other.
+    const fn max_potency(self) -> u32 {
+        match self {
+            Self::Poison => 3,
+            _ => 1,
+        }
+    }
+
+    /// The letter on the icon in the HUD.
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Poison => "P",
+            Self::Regeneration => "R",
+            Self::Haste => "H",
+            Self::Confusion => "C",
+            Self::Hallucination => "?",
+        }
+    }
+
+    /// The color of the icon in the HUD.
+    pub const fn color(self) -> Color {
+        match self {
+            Self::Poison => Color::new(0.3, 0.75, 0.2, 1.),
+            Self::Regeneration => Color::new(0.9, 0.3, 0.4, 1.),
+            Self::Haste => Color::new(0.3, 0.7, 0.95, 1.),
+            Self::Confusion => Color::new(0.75, 0.6, 0.2, 1.),
+            Self::Hallucination => Color::new(0.8, 0.35, 0.9, 1.),
+        }
+    }
+}
+
+impl std::fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// An effect that lasts for some turns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// How many turns are left.
+    pub turns: u32,
+    /// How strong the effect is, grows with the stacked doses.
+    pub potency: u32,
+}
+
+impl StatusEffect {
+    pub const fn new(kind: StatusKind, turns: u32) -> Self {
+        Self {
+            kind,
+            turns,
+            potency: 1,
+        }
+    }
+}
+
+/// What the effects did during one turn.
+#[derive(Debug, Default)]
+pub struct StatusTick {
+    pub damage: i32,
+    pub healing: i32,
+    /// The effects that wore off.
+    pub expired: Vec<StatusKind>,
+}
+
+/// All the effects on a creature, at most one of every kind.
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Puts the effect on, an effect of the same kind lasts for the longer
+    /// of the two durations and gets stronger if the kind stacks.
+    pub fn add(&mut self, effect: StatusEffect) {
+        match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(existing) => {
+                existing.turns = existing.turns.max(effect.turns);
+                existing.potency =
+                    (existing.potency + effect.potency).min(effect.kind.max_potency());
+            }
+            None => self.effects.push(effect),
+        }
+    }
+
+    /// Applies the effects for one turn and wears them off.
+    pub fn tick(&mut self) -> StatusTick {
+        let mut tick = StatusTick::default();
+        for effect in self.effects.iter_mut() {
+            match effect.kind {
+                StatusKind::Poison => tick.damage += effect.potency as i32,
+                StatusKind::Regeneration => tick.healing += effect.potency as i32,
+                _ => {}
+            }
+            effect.turns = effect.turns.saturating_sub(1);
+            if effect.turns == 0 {
+                tick.expired.push(effect.kind);
+            }
+        }
+        self.effects.retain(|effect| effect.turns > 0);
+        tick
+    }
+
+    /// How much faster the effects make the creature.
+    pub fn speed_bonus(&self) -> i32 {
+        if self.has(StatusKind::Haste) {
+            50
+        } else {
+            0
+        }
+    }
+
+    /// How much further the effects let the creature see.
+    pub fn vision_bonus(&self) -> i16 {
+        if self.has(StatusKind::Confusion) {
+            -4
+        } else {
+            0
+        }
+    }
+}
+
+impl Entities {
+    /// Applies the player's status effects for one turn.
+    pub(super) fn tick_status(&mut self) {
+        let tick = self.player.status.tick();
+        let stats = &mut self.player.stats;
+        stats.hp = (stats.hp + tick.healing).min(stats.max_hp);
+        if tick.damage > 0 && stats.take_damage(tick.damage) {
+            self.player_dies("Died of poison".to_string());
+        }
+        for kind in tick.expired {
+            let message = match kind {
+                StatusKind::Poison => "The poison wears off.",
+                StatusKind::Regeneration => "Your wounds stop closing.",
+                StatusKind::Haste => "You slow down again.",
+                StatusKind::Confusion => "Your head clears up.",
+                StatusKind::Hallucination => "The world looks normal again.",
+            };
+            self.events.message(MessageKind::Info, message);
+        }
+        self.player.actor.speed = NORMAL_SPEED + self.player.status.speed_bonus();
+    }
+
+    /// Applies the effects on the creature at `index` for one turn,
+    /// the haste and its wearing off change how fast the creature acts.
+    pub(super) fn tick_creature_status(&mut self, index: usize) {
+        let creature = &mut self.entities[index];
+        if creature.removed {
+            return;
+        }
+        let tile = creature.tile;
+        let tick = creature.status.tick();
+        if let Some(actor) = creature.actor.as_mut() {
+            actor.speed =
+                Entity::speed(tile).unwrap_or(NORMAL_SPEED) + creature.status.speed_bonus();
+        }
+        let stats = match creature.stats.as_mut() {
+            Some(stats) => stats,
+            None => return,
+        };
+        stats.hp = (stats.hp + tick.healing).min(stats.max_hp);
+        if tick.damage > 0 && stats.take_damage(tick.damage) {
+            creature.removed = true;
+            self.events
+                .message(MessageKind::Combat, format!("The {} dies of poison.", tile));
+            self.events.emit(GameEvent::EntityDied { tile });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_kind_keeps_the_longer_duration() {
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect::new(StatusKind::Haste, 10));
+        status.add(StatusEffect::new(StatusKind::Haste, 4));
+        let effects: Vec<&StatusEffect> = status.iter().collect();
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].turns, 10);
+        assert_eq!(effects[0].potency, 1);
+    }
+
+    #[test]
+    fn poison_stacks_up_to_its_limit() {
+        let mut status = StatusEffects::default();
+        for _ in 0..5 {
+            status.add(StatusEffect::new(StatusKind::Poison, 3));
+        }
+        assert_eq!(status.tick().damage, 3);
+    }
+
+    #[test]
+    fn tick_wears_the_effects_off() {
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect::new(StatusKind::Regeneration, 2));
+        status.add(StatusEffect::new(StatusKind::Confusion, 1));
+        let tick = status.tick();
+        assert_eq!(tick.healing, 1);
+        assert_eq!(tick.expired, vec![StatusKind::Confusion]);
+        assert!(!status.has(StatusKind::Confusion));
+        assert_eq!(status.tick().expired, vec![StatusKind::Regeneration]);
+        assert_eq!(status.iter().count(), 0);
+    }
+}
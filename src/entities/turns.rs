@@ -0,0 +1,230 @@
+use crate::coords::{distance, AbsolutePosition, LocalPosition, CHUNK_SIZE};
+use crate::entities::entities::{Entities, Entity, MAX_SIGHT, STUMBLE_CHANCE};
+use crate::entities::status::StatusKind;
+use crate::events::GameEvent;
+use crate::generator::Generator;
+use crate::message_log::MessageKind;
+use crate::tile_types::TileType;
+use crate::weather::Weather;
+use crate::world::World;
+
+/// How far the creatures notice the player from.
+const CREATURE_SIGHT: f32 = 8.;
+/// How many places are tried for the night creatures to spawn at every night turn.
+const NIGHT_SPAWN_ATTEMPTS: u64 = 3;
+/// The percent chance for the NPC to take a step every turn.
+const NPC_STROLL_CHANCE: i32 = 25;
+/// How many tiles the NPCs stroll away from their home.
+const NPC_HOME_RADIUS: f32 = 3.;
+/// The percent chance for a puddle to appear every turn of the rain.
+const PUDDLE_CHANCE: i32 = 40;
+/// The percent chance for every puddle to dry out every turn without the rain.
+const PUDDLE_DRY_CHANCE: i32 = 2;
+
+impl Entities {
+    /// Advances the world turn by turn until the player has the energy to act again.
+    /// Every actor gains energy each turn and acts as long as it has enough,
+    /// so faster actors get to act more often than the slower ones.
+    pub(super) fn run_turns(&mut self, world: &World) {
+        while !self.player.actor.is_ready() && !self.player.is_dead() {
+            self.clock.tick();
+            self.update_weather(world);
+            self.digest();
+            self.tick_status();
+            self.player.actor.gain_energy();
+            for index in 0..self.entities.len() {
+                self.tick_creature_status(index);
+                if let Some(actor) = self.entities[index].actor.as_mut() {
+                    actor.gain_energy();
+                }
+                while self.entities[index]
+                    .actor
+                    .is_some_and(|actor| actor.is_ready())
+                {
+                    self.take_turn(index, world);
+                }
+            }
+            if !self.clock.is_night() {
+                self.fade_nocturnal_entities();
+            }
+            self.clean_up();
+            self.events.emit(GameEvent::TurnEnded(self.clock.turn));
+        }
+    }
+
+    /// Lets the actor at `index` do its action.
+    /// Creatures attack the player next to them, chase the player they see
+    /// and wander around otherwise.
+    fn take_turn(&mut self, index: usize, world: &World) {
+        let entity = &mut self.entities[index];
+        if let Some(actor) = entity.actor.as_mut() {
+            actor.spend_energy();
+        }
+        if entity.removed || self.player.is_dead() {
+            return;
+        }
+        // The NPCs just stroll around from time to time.
+        if entity.stats.is_none() {
+            if Entity::npc(entity.tile).is_some() && self.rng.chance(NPC_STROLL_CHANCE) {
+                let step = [(1, 0), (-1, 0), (0, 1), (0, -1)][self.rng.range(0, 4) as usize];
+                let target = entity.get_absolute_position().add_to_local(step);
+                let near_home = entity.home.as_ref().is_none_or(|home| {
+                    distance(
+                        target.get_absolute_position_f32(),
+                        home.get_absolute_position_f32(),
+                    ) <= NPC_HOME_RADIUS
+                });
+                if near_home {
+                    self.move_creature(index, step, world);
+                }
+            }
+            return;
+        }
+
+        let (x, y) = entity.get_absolute_position_f32();
+        let (player_x, player_y) = self.player.entity.get_absolute_position_f32();
+        let (delta_x, delta_y) = (player_x - x, player_y - y);
+        if delta_x.abs().max(delta_y.abs()) <= 1. {
+            self.creature_attacks(index);
+            return;
+        }
+
+        let confused = entity.status.has(StatusKind::Confusion) && self.rng.chance(STUMBLE_CHANCE);
+        let step = if confused {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)][self.rng.range(0, 4) as usize]
+        } else if distance((x, y), (player_x, player_y)) < CREATURE_SIGHT {
+            if delta_x.abs() > delta_y.abs() {
+                (delta_x.signum() as i16, 0)
+            } else {
+                (0, delta_y.signum() as i16)
+            }
+        } else {
+            match self.rng.range(0, 8) {
+                0 => (1, 0),
+                1 => (-1, 0),
+                2 => (0, 1),
+                3 => (0, -1),
+                _ => (0, 0),
+            }
+        };
+        if step != (0, 0) {
+            self.move_creature(index, step, world);
+        }
+    }
+
+    /// Moves the creature at `index` by `step` if the place is free and loaded.
+    fn move_creature(&mut self, index: usize, step: (i16, i16), world: &World) {
+        let target = self.entities[index]
+            .get_absolute_position()
+            .add_to_local(step);
+        let player_pos = self.player.entity.get_absolute_position();
+        let occupied = self.get_entity_at_pos(&target).is_some()
+            || (target.chunk == player_pos.chunk && target.local == player_pos.local);
+        if !occupied && world.is_walkable(&target) && self.loaded_locations.contains(&target.chunk)
+        {
+            self.entities[index].set_position(target.clone());
+            self.events.emit(GameEvent::EntityMoved {
+                tile: self.entities[index].tile,
+                to: target,
+            });
+        }
+    }
+
+    /// Creatures of the night come out in the darkness, away from the player's sight.
+    pub(super) fn spawn_night_creatures(
+        &mut self,
+        world: &World,
+        generator: &Generator,
+        turns: u64,
+    ) {
+        for _ in 0..turns * NIGHT_SPAWN_ATTEMPTS {
+            if self.loaded_locations.is_empty() {
+                return;
+            }
+            let chunk_index = self.rng.range(0, self.loaded_locations.len() as i32);
+            let chunk = self.loaded_locations[chunk_index as usize];
+            let local = LocalPosition::new(
+                self.rng.range(0, CHUNK_SIZE.into()) as i16,
+                self.rng.range(0, CHUNK_SIZE.into()) as i16,
+            );
+            let position = AbsolutePosition { chunk, local };
+            let number = self.rng.range(0, 256) as u8;
+            let ground_tile = match world.get_tile(&position) {
+                Some(tile) => *tile,
+                None => continue,
+            };
+            let creature = generator
+                .night_entity(ground_tile, number, local)
+                .filter(|entity| entity.stats.is_some());
+            let hidden = !self.fov.is_visible(position.get_absolute_position_i32());
+            if let Some(mut creature) = creature {
+                if hidden && self.get_entity_at_pos(&position).is_none() {
+                    creature.set_chunk_position(chunk);
+                    self.entities.push(creature);
+                }
+            }
+        }
+    }
+
+    /// Moves the weather towards the forecast for the player's region,
+    /// the rain leaves puddles behind and they dry out when it stops.
+    fn update_weather(&mut self, world: &World) {
+        let forecast = Weather::forecast(self.seed, self.clock.turn, self.player.entity.chunk_pos);
+        self.weather.update(forecast);
+
+        if self.weather.is_raining() {
+            if self.rng.chance(PUDDLE_CHANCE) {
+                self.spawn_puddle(world);
+            }
+        } else {
+            for entity in self.entities.iter_mut() {
+                if entity.tile == TileType::Puddle && self.rng.chance(PUDDLE_DRY_CHANCE) {
+                    entity.removed = true;
+                }
+            }
+        }
+    }
+
+    /// Fills a puddle somewhere around the player on the bare ground.
+    fn spawn_puddle(&mut self, world: &World) {
+        let sight = i32::from(MAX_SIGHT);
+        let offset = (
+            self.rng.range(-sight, sight + 1) as i16,
+            self.rng.range(-sight, sight + 1) as i16,
+        );
+        let position = self
+            .player
+            .entity
+            .get_absolute_position()
+            .add_to_local(offset);
+        let ground = world.get_tile(&position);
+        let bare_ground = matches!(
+            ground,
+            Some(TileType::GrassFloor) | Some(TileType::StoneFloor)
+        );
+        if bare_ground && self.get_entity_at_pos(&position).is_none() {
+            self.entities.push(Entity::new(position, TileType::Puddle));
+        }
+    }
+
+    /// The night creatures don't survive the daylight.
+    fn fade_nocturnal_entities(&mut self) {
+        let mut faded = false;
+        for entity in self.entities.iter_mut() {
+            if Entity::is_nocturnal(entity.tile) && !entity.removed {
+                entity.removed = true;
+                faded |= entity.stats.is_some();
+            }
+        }
+        // The ones in the chunks out of view fade too, they don't come back in the day.
+        for stored in self.entities_store.values_mut() {
+            stored.retain(|entity| !Entity::is_nocturnal(entity.tile));
+        }
+        if faded {
+            self.events.message(
+                MessageKind::Discovery,
+                "The creatures of the night fade with the dawn.",
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::coords::{AbsolutePosition, ChunkPosition};
+use crate::entities::items::Item;
+use crate::message_log::MessageKind;
+use crate::tile_types::TileType;
+
+use std::collections::VecDeque;
+
+/// Something that happened in the game, for the systems that care about it.
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    /// The entity with the tile, or the player, stepped to the position.
+    EntityMoved {
+        tile: TileType,
+        to: AbsolutePosition,
+    },
+    /// The player got the item into the inventory.
+    ItemPickedUp(Item),
+    /// The player dropped the item from the inventory.
+    ItemDropped(Item),
+    /// The player stands next to the entity with the tile.
+    PlayerNextTo(TileType),
+    /// The player finished the quest with the title and earned the reward.
+    QuestCompleted { title: String, reward: Item },
+    /// The creature with the tile, or the player, died.
+    EntityDied { tile: TileType },
+    /// The entities of the chunk came into view, `first_visit` when it was never seen before.
+    ChunkLoaded {
+        chunk: ChunkPosition,
+        first_visit: bool,
+    },
+    /// The turn with the number is over.
+    TurnEnded(u64),
+    /// Something to tell the player in the message log.
+    Message(MessageKind, String),
+    /// The player earned the achievement with the name.
+    AchievementUnlocked(&'static str),
+}
+
+/// Anything that reacts to the events, it can emit the new events in response.
+pub trait EventListener {
+    fn on_event(&mut self, event: &GameEvent, bus: &mut EventBus);
+}
+
+/// The queue of the events waiting to be handed out to the listeners.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    queue: VecDeque<GameEvent>,
+}
+
+impl EventBus {
+    pub fn emit(&mut self, event: GameEvent) {
+        self.queue.push_back(event);
+    }
+
+    /// Emits the message for the message log.
+    pub fn message<S: Into<String>>(&mut self, kind: MessageKind, text: S) {
+        self.emit(GameEvent::Message(kind, text.into()));
+    }
+
+    /// Hands every event to all the listeners in order,
+    /// the events emitted by the listeners are handed out too.
+    pub fn dispatch(&mut self, listeners: &mut [&mut dyn EventListener]) {
+        while let Some(event) = self.queue.pop_front() {
+            for listener in listeners.iter_mut() {
+                listener.on_event(&event, self);
+            }
+        }
+    }
+}
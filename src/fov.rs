@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+/// The tiles that can be seen from some place.
+/// Positions are the absolute tile coordinates.
+#[derive(Debug, Default)]
+pub struct FieldOfView {
+    visible: HashSet<(i32, i32)>,
+}
+
+impl FieldOfView {
+    /// Casts a ray to every tile on the edge of the square around the `origin`,
+    /// the tiles within the `radius` along the ray are visible until something blocks the sight.
+    /// The tile that blocks is visible too.
+    pub fn compute<F>(&mut self, origin: (i32, i32), radius: i16, blocks_sight: F)
+    where
+        F: Fn((i32, i32)) -> bool,
+    {
+        self.visible.clear();
+        self.visible.insert(origin);
+        let radius = i32::from(radius);
+        for offset in -radius..=radius {
+            for edge in [
+                (offset, radius),
+                (offset, -radius),
+                (radius, offset),
+                (-radius, offset),
+            ] {
+                let to = (origin.0 + edge.0, origin.1 + edge.1);
+                self.cast_ray(origin, to, radius, &blocks_sight);
+            }
+        }
+    }
+
+    pub fn is_visible(&self, position: (i32, i32)) -> bool {
+        self.visible.contains(&position)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(i32, i32)> {
+        self.visible.iter()
+    }
+
+    /// Walks the Bresenham line from `from` to `to` and marks the tiles within the `radius`
+    /// as visible, up to the first one that blocks the sight.
+    fn cast_ray<F>(&mut self, from: (i32, i32), to: (i32, i32), radius: i32, blocks_sight: &F)
+    where
+        F: Fn((i32, i32)) -> bool,
+    {
+        let (delta_x, delta_y) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+        let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let mut error = delta_x + delta_y;
+        let (mut x, mut y) = from;
+        while (x, y) != to {
+            let doubled_error = 2 * error;
+            if doubled_error >= delta_y {
+                error += delta_y;
+                x += step_x;
+            }
+            if doubled_error <= delta_x {
+                error += delta_x;
+                y += step_y;
+            }
+            let (distance_x, distance_y) = (x - from.0, y - from.1);
+            if distance_x * distance_x + distance_y * distance_y > radius * radius {
+                return;
+            }
+            self.visible.insert((x, y));
+            if blocks_sight((x, y)) {
+                return;
+            }
+        }
+    }
+}
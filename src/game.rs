@@ -0,0 +1,725 @@
+use macroquad::camera::Camera2D;
+use macroquad::prelude::*;
+
+use crate::achievements::Achievements;
+use crate::camera::{mouse_position_relative_to, tile_at, Camera};
+use crate::clock::Clock;
+use crate::controls::{Command, KeyBindings, KeyRepeat};
+use crate::coords::{
+    get_checked_position, AbsolutePosition, ChunkPosition, LocalPosition, CHUNK_SIZE,
+};
+use crate::crafting_screen::CraftingScreen;
+use crate::dialogue_screen::DialogueScreen;
+use crate::entities::crafting::Recipe;
+use crate::entities::dialogue::Dialogues;
+use crate::entities::entities::Entities;
+use crate::entities::hunger::{Hunger, HungerState};
+use crate::entities::lore::Lore;
+use crate::entities::player::Action;
+use crate::entities::quests::QuestBook;
+use crate::entities::status::StatusEffects;
+use crate::generator::Generator;
+use crate::graphics::lighting::LightMap;
+use crate::graphics::tile_atlas::TileAtlas;
+use crate::inventory_screen::InventoryScreen;
+use crate::journal_screen::JournalScreen;
+use crate::look_mode::LookMode;
+use crate::message_log::{MessageKind, MessageLog};
+use crate::minimap::Minimap;
+use crate::save::{SaveFile, SAVE_PATH};
+use crate::score::Score;
+use crate::settings::Settings;
+use crate::statistics::Statistics;
+use crate::tooltip::{describe_tile, draw_tooltip, is_seen};
+use crate::weather::{Weather, WeatherKind};
+use crate::world::World;
+
+/// What the game is showing and what the input goes to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameState {
+    /// The title menu, where the games are started.
+    Title,
+    /// The settings and the keys, opened from the title or the pause menu.
+    Settings,
+    /// The game is played, the inventory and the other screens open over it.
+    Playing,
+    /// The game waits behind the pause menu.
+    Paused,
+    /// The player is dead, the summary of the run is shown.
+    GameOver,
+}
+
+/// Everything loaded from the data files, shared by all the games.
+#[derive(Debug, Clone)]
+pub struct GameData {
+    pub quest_book: QuestBook,
+    pub lore: Lore,
+    pub recipes: Vec<Recipe>,
+    pub dialogues: Dialogues,
+}
+
+/// A single game, from the start to the death of the player.
+/// Starting a new game makes a new one, so nothing is left from the old one.
+pub struct Game {
+    /// The place that holds terrain.
+    world: World,
+    generator: Generator,
+    /// The container for all of the entities.
+    pub entities: Entities,
+    /// The messages about what happens in the game.
+    log: MessageLog,
+    /// The screen with the player's items.
+    inventory_screen: InventoryScreen,
+    /// The screen with the recipes.
+    crafting_screen: CraftingScreen,
+    /// The conversations with the NPCs.
+    dialogue_screen: DialogueScreen,
+    /// The screen with the player's quests.
+    journal_screen: JournalScreen,
+    /// The numbers about the run, the earned achievements and the score, all fed by the game events.
+    statistics: Statistics,
+    score: Score,
+    achievements: Achievements,
+    /// How much light falls on the tiles around the player.
+    light_map: LightMap,
+    camera: Camera,
+    /// We need to save the state of the mouse button
+    /// to detect mouse clicks, as opposed to just "is pressed" state.
+    left_mouse_pressed: bool,
+    /// Repeats the movement while the keys are held down.
+    key_repeat: KeyRepeat,
+    /// How long until the next step of the run.
+    run_timer: f32,
+    /// Examining the tiles with a cursor, the camera is detached from the player.
+    look_mode: LookMode,
+    /// The camera glides back to the player after looking around.
+    camera_returning: bool,
+    /// The map of the explored world, in the corner or over the whole screen.
+    minimap: Minimap,
+}
+
+impl Game {
+    /// Starts a new game in the world made from the seed.
+    pub fn new(seed: i32, data: &GameData) -> Self {
+        Self {
+            world: World::default(),
+            generator: Generator::new(seed),
+            entities: Entities::new(seed, data.quest_book.clone(), data.lore.clone()),
+            log: MessageLog::default(),
+            inventory_screen: InventoryScreen::default(),
+            crafting_screen: CraftingScreen::new(data.recipes.clone()),
+            dialogue_screen: DialogueScreen::new(data.dialogues.clone()),
+            journal_screen: JournalScreen::default(),
+            statistics: Statistics::default(),
+            score: Score::default(),
+            achievements: Achievements::default(),
+            light_map: LightMap::default(),
+            camera: Camera::default(),
+            left_mouse_pressed: false,
+            key_repeat: KeyRepeat::default(),
+            run_timer: 0.,
+            look_mode: LookMode::default(),
+            camera_returning: false,
+            minimap: Minimap::default(),
+        }
+    }
+
+    /// Restores the game from the save file.
+    pub fn load(data: &GameData) -> std::io::Result<Option<Self>> {
+        let file = SaveFile::read(SAVE_PATH)?;
+        let loaded = Entities::load(&file, data.quest_book.clone(), data.lore.clone())
+            .zip(World::load(&file))
+            .zip(Statistics::load(&file))
+            .zip(Score::load(&file).zip(Achievements::load(&file)))
+            .map(|(((entities, world), statistics), (score, achievements))| {
+                let mut game = Self {
+                    world,
+                    generator: Generator::new(entities.seed()),
+                    entities,
+                    statistics,
+                    score,
+                    achievements,
+                    ..Self::new(0, data)
+                };
+                // The camera starts on the player instead of gliding over from the world's origin.
+                game.camera.set_target(
+                    game.entities
+                        .player
+                        .entity
+                        .get_absolute_position_f32()
+                        .into(),
+                );
+                game
+            });
+        Ok(loaded)
+    }
+
+    /// Writes the game to the save file.
+    pub fn save(&mut self) {
+        let mut file = SaveFile::default();
+        self.entities.save(&mut file);
+        self.world.save(&mut file);
+        self.statistics.save(&mut file);
+        self.score.save(&mut file);
+        self.achievements.save(&mut file);
+        match file.write(SAVE_PATH) {
+            Ok(()) => self.log.add(MessageKind::Info, "The game is saved."),
+            Err(error) => self
+                .log
+                .add(MessageKind::Warning, format!("Can't save: {}", error)),
+        }
+    }
+
+    /// Replaces the game with the saved one, the messages are kept.
+    pub fn reload(&mut self, data: &GameData) {
+        match Self::load(data) {
+            Ok(Some(loaded)) => {
+                let log = std::mem::take(&mut self.log);
+                *self = loaded;
+                self.log = log;
+                self.log.add(MessageKind::Info, "The game is loaded.");
+            }
+            Ok(None) => self
+                .log
+                .add(MessageKind::Warning, "The save file is broken."),
+            Err(error) => self
+                .log
+                .add(MessageKind::Warning, format!("Can't load: {}", error)),
+        }
+    }
+
+    /// Adds a warning to the message log, for the things that went wrong outside of the game.
+    pub fn warn<S: Into<String>>(&mut self, text: S) {
+        self.log.add(MessageKind::Warning, text);
+    }
+
+    /// Is any of the screens over the game open?
+    pub const fn screen_open(&self) -> bool {
+        self.log.show_history
+            || self.inventory_screen.open
+            || self.crafting_screen.open
+            || self.journal_screen.open
+            || self.dialogue_screen.is_open()
+            || self.look_mode.active
+            || self.minimap.full_screen
+    }
+
+    /// Handles the input and lets the world take its turns.
+    pub fn update(&mut self, data: &GameData, settings: &Settings) {
+        // ===========Input===========
+        // Get the Vec2 position of the mouse inside the game world.
+        let mouse_position = mouse_position_relative_to(&self.camera);
+
+        // Select the clicked on tile and update the left mouse button state.
+        let (left_mouse_pressed, cursor) = handle_mouse(self.left_mouse_pressed, mouse_position);
+        self.left_mouse_pressed = left_mouse_pressed;
+
+        // Log the info about the tile we clicked, the unseen tiles as they are remembered.
+        if let Some(cursor) = cursor {
+            let position = cursor.get_absolute_position_i32();
+            if is_seen(&self.entities, &self.light_map, position) {
+                self.log.add(
+                    MessageKind::Discovery,
+                    format!("Terrain: {}", self.world.get_tile(&cursor).unwrap()),
+                );
+                // The engravings are read instead.
+                if !self.entities.examine_engraving(&cursor) {
+                    if let Some(entity) = self.entities.get_entity_at_pos(&cursor) {
+                        self.log.add(MessageKind::Discovery, entity.to_string());
+                    }
+                }
+            } else if let Some(remembered) = self.entities.memory.get(position) {
+                self.log.add(
+                    MessageKind::Discovery,
+                    format!("You remember {} here.", remembered.terrain),
+                );
+                for tile in &remembered.entities {
+                    self.log.add(
+                        MessageKind::Discovery,
+                        format!("You remember {} here.", tile),
+                    );
+                }
+            } else {
+                self.log
+                    .add(MessageKind::Discovery, "You haven't seen this place.");
+            }
+        }
+
+        // Save or load the game.
+        if settings.bindings.pressed(Command::QuickSave) {
+            self.save();
+        }
+        if settings.bindings.pressed(Command::QuickLoad) {
+            self.reload(data);
+        }
+
+        // Looking around and the world map take over the keyboard, the world waits meanwhile.
+        if self.minimap.full_screen {
+            self.minimap.handle_input(&settings.bindings);
+        } else if self.look_mode.active {
+            if !self
+                .look_mode
+                .handle_input(&settings.bindings, mouse_position)
+            {
+                self.camera_returning = true;
+            }
+            self.camera.scroll(
+                settings.scroll_speed(),
+                settings.zoom_speed(),
+                &settings.bindings,
+            );
+        } else if !self.screen_open() && settings.bindings.pressed(Command::Look) {
+            self.look_mode.start(
+                self.entities
+                    .player
+                    .entity
+                    .get_absolute_position()
+                    .get_absolute_position_i32(),
+            );
+        } else if !self.screen_open() && settings.bindings.pressed(Command::Map) {
+            self.minimap.open(&settings.bindings);
+        } else {
+            // The message log can be scrolled or shown as a full history.
+            self.log.handle_input(&settings.bindings);
+            self.handle_screens_and_movement(settings);
+        }
+        // ===========Update===========
+
+        // We need to know the player's chunk to see what chunks need to be
+        // loaded or unloaded.
+        let player_pos = self.entities.player.entity.chunk_pos;
+        // Load or generate the chunks near the player.
+        self.world.update(&player_pos, &self.generator);
+
+        // Load, generate or update all of the entities.
+        let view_changed = self.entities.update(&mut self.world, &self.generator);
+        // Let the other systems react to what happened during the update.
+        let mut events = std::mem::take(&mut self.entities.events);
+        events.dispatch(&mut [
+            &mut self.log,
+            &mut self.entities.player.journal,
+            &mut self.statistics,
+            &mut self.achievements,
+            &mut self.score,
+        ]);
+        if let Some((npc, position)) = self.entities.talking_to.take() {
+            self.entities
+                .input(self.dialogue_screen.start(npc, position));
+        }
+        // Light up the surroundings, walls and closed doors cast shadows.
+        if view_changed {
+            let mut lights = self.world.light_sources();
+            lights.append(&mut self.entities.light_sources());
+            self.light_map.compute(
+                &lights,
+                &self.entities.sight_blockers(),
+                self.entities
+                    .weather
+                    .filter_light(self.entities.clock.ambient_light()),
+            );
+        }
+
+        // Point the camera to the new position of the player before the drawing stage.
+        // While looking around it follows the cursor and glides back afterwards.
+        let player: Vec2 = self
+            .entities
+            .player
+            .entity
+            .get_absolute_position_f32()
+            .into();
+        let damping = settings.camera_damping();
+        if self.look_mode.active {
+            if let Some(cursor) = self.look_mode.follows() {
+                self.camera.glide_to(cursor, damping);
+            }
+        } else if self.camera_returning {
+            self.camera_returning = !self.camera.glide_to(player, damping);
+        } else {
+            self.camera
+                .follow(player, damping, settings.camera_deadzone());
+        }
+    }
+
+    /// Hands the keyboard to the open screen, or moves the player if none is open.
+    fn handle_screens_and_movement(&mut self, settings: &Settings) {
+        // The conversation takes over the keyboard until it's over.
+        if self.dialogue_screen.is_open() {
+            self.entities
+                .input(self.dialogue_screen.handle_input(&settings.bindings));
+        }
+        // The journal only shows the quests, the game waits while it is open.
+        let talking = self.dialogue_screen.is_open();
+        if !talking {
+            self.journal_screen.handle_input(&settings.bindings);
+        }
+        // The inventory and the crafting screens take over the keyboard while they are open.
+        let overlay = self.log.show_history || self.journal_screen.open || talking;
+        if !overlay && !self.crafting_screen.open {
+            let action = self
+                .inventory_screen
+                .handle_input(&self.entities.player, &settings.bindings);
+            self.entities.input(action);
+        }
+        if !overlay && !self.inventory_screen.open {
+            let action = self
+                .crafting_screen
+                .handle_input(&self.entities.player, &settings.bindings);
+            self.entities.input(action);
+        }
+        // Entities container handles player movement.
+        if !overlay && !self.inventory_screen.open && !self.crafting_screen.open {
+            let action = handle_keyboard(&mut self.camera, settings, &mut self.key_repeat);
+            if action.is_some() {
+                self.run_timer = settings.key_repeat_rate();
+            }
+            self.entities.input(action);
+        }
+        // The run goes on by itself, a step at the key repeat rate,
+        // and waits while any of the screens is open.
+        self.run_timer -= get_frame_time();
+        if self.entities.player.action.is_none() && self.run_timer <= 0. && !self.screen_open() {
+            if let Some(step) = self.entities.next_run_step() {
+                self.entities.input(Some(step));
+                self.run_timer = settings.key_repeat_rate();
+            }
+        }
+    }
+
+    /// Draws the world, the entities and the interface over them.
+    pub fn draw(&mut self, tile_atlas: &TileAtlas, bindings: &KeyBindings) {
+        // Fill the canvas with the background color.
+        clear_background(Color {
+            r: 40.0 / 255.0,
+            g: 40.0 / 255.0,
+            b: 40.0 / 255.0,
+            a: 1.0,
+        });
+
+        // --- Camera space, render game objects.
+        let (target, zoom) = self.camera.get();
+        let zoom = Vec2::new(zoom.x, -zoom.y);
+        set_camera(&Camera2D {
+            target,
+            zoom,
+            ..Camera2D::default()
+        });
+
+        // We draw everything besides the ui in camera space.
+        // World needs to know what terrain the player sees
+        // and how much light falls on it.
+        self.world
+            .draw(tile_atlas, &self.entities.fov, &self.light_map);
+        // Entities container already knows about the player.
+        self.entities.draw(tile_atlas, &self.light_map);
+        if self.look_mode.active {
+            self.look_mode.draw_cursor();
+        }
+
+        // Draw the mouse cursor. As a small circle.
+        let mouse_position = mouse_position_relative_to(&self.camera);
+        draw_circle(
+            mouse_position.x,
+            mouse_position.y,
+            0.1,
+            Color::from_rgba(100, 75, 120, 255),
+        );
+
+        // --- Fixed screen space, render ui.
+        set_default_camera();
+        self.entities.weather.draw();
+        let entities = &self.entities;
+        draw_text(
+            format!("Collect coins! you have {}.", self.score.points).as_str(),
+            5.,
+            5.,
+            30.,
+            Color::new(40., 80., 170., 200.),
+        );
+        let stats = &entities.player.stats;
+        draw_text(
+            format!("HP: {}/{}", stats.hp, stats.max_hp).as_str(),
+            5.,
+            30.,
+            30.,
+            Color::new(0.9, 0.3, 0.3, 1.),
+        );
+        draw_hunger(&entities.player.hunger);
+        draw_status(&entities.player.status);
+        draw_clock(&entities.clock, &entities.weather);
+        if !self.minimap.full_screen {
+            self.minimap.draw(entities);
+        }
+        self.log.draw(5., 55., bindings);
+        if let Some(quest) = entities.player.journal.active().next() {
+            draw_text(
+                format!("Quest: {} ({})", quest.title, quest.progress_text()).as_str(),
+                5.,
+                210.,
+                22.,
+                YELLOW,
+            );
+        }
+        if self.inventory_screen.open {
+            self.inventory_screen.draw(&entities.player, bindings);
+        }
+        if self.crafting_screen.open {
+            self.crafting_screen.draw(&entities.player, bindings);
+        }
+        if self.journal_screen.open {
+            self.journal_screen.draw(&entities.player, bindings);
+        }
+        self.dialogue_screen.draw(bindings);
+        if self.look_mode.active {
+            LookMode::draw_panel(
+                &self
+                    .look_mode
+                    .describe(&self.world, entities, &self.light_map),
+                bindings,
+            );
+        } else if !self.screen_open() {
+            // Whatever the player can see under the mouse is described next to it.
+            let hovered = tile_at(mouse_position_relative_to(&self.camera));
+            if is_seen(entities, &self.light_map, hovered) {
+                draw_tooltip(&describe_tile(
+                    &self.world,
+                    entities,
+                    &self.light_map,
+                    hovered,
+                ));
+            }
+        }
+        if self.log.show_history {
+            self.log.draw_history(bindings);
+        }
+        if self.minimap.full_screen {
+            self.minimap.draw(entities);
+        }
+    }
+
+    /// Draws the screen shown after the player's death.
+    pub fn draw_game_over(&self, bindings: &KeyBindings) {
+        clear_background(BLACK);
+        let entities = &self.entities;
+        let cause = entities
+            .player
+            .cause_of_death
+            .as_deref()
+            .unwrap_or("Died of unknown causes");
+        let mut lines = vec![
+            "GAME OVER".to_string(),
+            format!("{} on turn {}.", cause, entities.clock.turn),
+            format!("Final score: {}", self.score.points),
+        ];
+        lines.extend(self.statistics.summary());
+        let unlocked: Vec<&str> = self.achievements.unlocked().collect();
+        if !unlocked.is_empty() {
+            lines.push(format!("Achievements: {}", unlocked.join(", ")));
+        }
+        lines.push(format!(
+            "Press {} for the title screen, {} to quit.",
+            bindings.key_name(Command::Confirm),
+            bindings.key_name(Command::Cancel)
+        ));
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(
+                line.as_str(),
+                screen_width() / 2. - 200.,
+                screen_height() / 3. + index as f32 * 40.,
+                40.,
+                WHITE,
+            );
+        }
+    }
+}
+
+/// Draws how full the player's stomach is next to the hit points.
+fn draw_hunger(hunger: &Hunger) {
+    let (x, y, width, height) = (160., 14., 120., 16.);
+    let state = hunger.state();
+    let color = match state {
+        HungerState::Fed => Color::new(0.4, 0.8, 0.3, 1.),
+        HungerState::Hungry => Color::new(0.9, 0.7, 0.2, 1.),
+        HungerState::Weak | HungerState::Starving => Color::new(0.9, 0.3, 0.2, 1.),
+    };
+    draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.5));
+    draw_rectangle(x, y, width * hunger.fraction(), height, color);
+    draw_rectangle_lines(x, y, width, height, 2., GRAY);
+    draw_text(state.to_string().as_str(), x + width + 8., 30., 24., color);
+}
+
+/// Draws an icon with the turns left for every status effect on the player.
+fn draw_status(status: &StatusEffects) {
+    let (mut x, y, size) = (380., 12., 20.);
+    for effect in status.iter() {
+        draw_rectangle(x, y, size, size, effect.kind.color());
+        draw_text(effect.kind.symbol(), x + 5., y + 16., 20., BLACK);
+        let turns = if effect.potency > 1 {
+            format!("{}x{}", effect.turns, effect.potency)
+        } else {
+            effect.turns.to_string()
+        };
+        draw_text(turns.as_str(), x + size + 3., y + 16., 18., WHITE);
+        x += size + 40.;
+    }
+}
+
+/// Draws the time of day in the top right corner,
+/// with the sun or the moon next to it.
+fn draw_clock(clock: &Clock, weather: &Weather) {
+    let x = screen_width() - 300.;
+    let daylight = clock.daylight();
+    let (body, glow) = if clock.is_night() {
+        (
+            Color::new(0.85, 0.9, 1., 1.),
+            Color::new(0.3, 0.4, 0.8, 0.4),
+        )
+    } else {
+        (
+            Color::new(1., 0.6 + 0.3 * daylight, 0.2, 1.),
+            Color::new(1., 0.8, 0.3, 0.4),
+        )
+    };
+    draw_circle(x, 22., 12., glow);
+    draw_circle(x, 22., 8., body);
+    draw_text(clock.to_string().as_str(), x + 20., 30., 24., WHITE);
+    if weather.kind != WeatherKind::Clear {
+        draw_text(
+            weather.kind.to_string().as_str(),
+            x + 20.,
+            52.,
+            22.,
+            LIGHTGRAY,
+        );
+    }
+}
+
+/// The commands that repeat while their keys are held down.
+const REPEATING: [Command; 9] = [
+    Command::MoveNorth,
+    Command::MoveSouth,
+    Command::MoveWest,
+    Command::MoveEast,
+    Command::MoveNorthWest,
+    Command::MoveNorthEast,
+    Command::MoveSouthWest,
+    Command::MoveSouthEast,
+    Command::Wait,
+];
+
+/// Handle the input from the keyboard.
+/// Returns the action the player wants to take, if any.
+fn handle_keyboard(
+    camera: &mut Camera,
+    settings: &Settings,
+    key_repeat: &mut KeyRepeat,
+) -> Option<Action> {
+    let bindings = &settings.bindings;
+    camera.scroll(settings.scroll_speed(), settings.zoom_speed(), bindings);
+    // Only one command at a time, the pressed keys don't add up.
+    let repeated = key_repeat.update(
+        bindings,
+        &REPEATING,
+        settings.key_repeat_delay(),
+        settings.key_repeat_rate(),
+    );
+    let mut step = repeated.and_then(Command::direction);
+    // Holding down one of the straight directions while pressing
+    // the one across it steps diagonally.
+    if let Some((right, up)) = step.as_mut() {
+        for command in [
+            Command::MoveNorth,
+            Command::MoveSouth,
+            Command::MoveWest,
+            Command::MoveEast,
+        ] {
+            let (held_right, held_up) = command.direction().unwrap_or_default();
+            if bindings.down(command) {
+                if *right == 0 {
+                    *right = held_right;
+                }
+                if *up == 0 {
+                    *up = held_up;
+                }
+            }
+        }
+    }
+
+    if let Some((right, up)) = step {
+        if bindings.down(Command::Run) {
+            Some(Action::Run(right, up))
+        } else {
+            Some(Action::Move(right, up))
+        }
+    } else if repeated == Some(Command::Wait) {
+        Some(Action::Wait)
+    } else if bindings.pressed(Command::PickUp) {
+        Some(Action::PickUp)
+    } else if bindings.pressed(Command::CloseDoor) {
+        Some(Action::CloseDoor)
+    } else if bindings.pressed(Command::Build) {
+        Some(Action::Build)
+    } else {
+        None
+    }
+}
+
+/// Handle the mouse. Print the coordinates where the mouse was clicked.
+/// Return the absolute position to be able to see what was clicked.
+fn handle_mouse(
+    left_mouse_pressed: bool,
+    mouse_position: Vec2,
+) -> (bool, Option<AbsolutePosition>) {
+    if is_mouse_button_down(MouseButton::Left) {
+        let (mut mouse_x, mut mouse_y) = (mouse_position.x, mouse_position.y);
+        mouse_x = mouse_x.floor();
+        mouse_y = mouse_y.floor();
+        let chunk_dimensions = f32::from(CHUNK_SIZE);
+        let (world_x, world_y) = (
+            (mouse_x / chunk_dimensions).floor(),
+            (mouse_y / chunk_dimensions).floor(),
+        );
+
+        if mouse_x < 0.0 {
+            mouse_x = chunk_dimensions - (-mouse_x % chunk_dimensions);
+        }
+        if mouse_y < 0.0 {
+            mouse_y = chunk_dimensions - (-mouse_y % chunk_dimensions);
+        }
+
+        if !left_mouse_pressed {
+            let (x, y) = (
+                mouse_x.abs() % chunk_dimensions,
+                mouse_y.abs() % chunk_dimensions,
+            );
+            debug!(
+                "World {world_x:.0}:{world_y:.0} | Position {x:.0}:{y:.0}",
+                world_x = world_x,
+                x = x,
+                world_y = world_y,
+                y = y
+            );
+            let AbsolutePosition {
+                chunk: chunk_pos,
+                local: local_pos,
+            } = get_checked_position(
+                ChunkPosition {
+                    x: world_x as i32,
+                    y: world_y as i32,
+                },
+                LocalPosition {
+                    x: (x + 1.) as i16,
+                    y: y as i16,
+                },
+            );
+            return (
+                true,
+                Some(AbsolutePosition {
+                    chunk: chunk_pos,
+                    local: local_pos,
+                }),
+            );
+        }
+        return (true, None);
+    }
+    (false, None)
+}
@@ -38,7 +38,8 @@ impl Generator {
                 positions_row.push(LocalPosition::new(x as i16, y as i16));
                 let number = *noise.get((y * CHUNK_SIZE + x) as usize).unwrap() as u8;
                 let tile_type = match number {
-                    0..=29 => TileType::StoneFloor,
+                    0..=6 => TileType::Lava,
+                    7..=29 => TileType::StoneFloor,
                     30..=239 => TileType::GrassFloor,
                     240..=255 => TileType::SandFloor,
                 };
@@ -55,6 +56,7 @@ impl Generator {
         x_offset: f32,
         y_offset: f32,
         chunk_terrain: &ChunkTerrain,
+        night: bool,
     ) -> Vec<Entity> {
         let noise = NoiseBuilder::gradient_2d_offset(
             x_offset,
@@ -75,12 +77,17 @@ impl Generator {
                 });
                 let pos = LocalPosition::new(x as i16, y as i16);
                 let number = *noise.get((y * CHUNK_SIZE + x) as usize).unwrap() as u8;
-                let entity = match ground_tile {
+                let night_entity = if night {
+                    self.night_entity(*ground_tile, number, pos)
+                } else {
+                    None
+                };
+                let entity = night_entity.or_else(|| match ground_tile {
                     TileType::SandFloor => self.sand_entity(number, pos),
                     TileType::GrassFloor => self.grass_entity(number, pos),
                     TileType::StoneFloor => self.stone_entity(number, pos),
                     _ => None,
-                };
+                });
                 if let Some(entity) = entity {
                     entities.push(entity)
                 }
@@ -89,8 +96,25 @@ impl Generator {
         entities
     }
 
+    /// The nocturnal entities, they are only generated at night.
+    pub fn night_entity(
+        &self,
+        ground_tile: TileType,
+        number: u8,
+        pos: LocalPosition,
+    ) -> Option<Entity> {
+        match (ground_tile, number) {
+            (TileType::GrassFloor, 213..=214) => Some(Entity::new_local(pos, TileType::MoonFlower)),
+            (TileType::GrassFloor | TileType::StoneFloor, 215..=216) => {
+                Some(Entity::new_local(pos, TileType::Ghost))
+            }
+            _ => None,
+        }
+    }
+
     fn grass_entity(&self, number: u8, pos: LocalPosition) -> Option<Entity> {
         match number {
+            217..=218 => Some(Entity::new_local(pos, TileType::Rat)),
             219..=220 => Some(Entity::new_local(pos, TileType::TreeStomp)),
             221..=225 => Some(Entity::new_local(pos, TileType::MushroomBrown)),
             226..=228 => Some(Entity::new_local(pos, TileType::MushroomOrange)),
@@ -115,6 +139,7 @@ impl Generator {
 
     fn stone_entity(&self, number: u8, pos: LocalPosition) -> Option<Entity> {
         match number {
+            243..=244 => Some(Entity::new_local(pos, TileType::Slime)),
             245..=250 => Some(Entity::new_local(pos, TileType::Placeholder)),
             251..=252 => Some(Entity::new_local(pos, TileType::Pond)),
             253..=255 => Some(Entity::new_local(pos, TileType::Coin)),
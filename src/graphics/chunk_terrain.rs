@@ -29,6 +29,15 @@ impl ChunkTerrain {
             .get(pos.x as usize)
             .expect("Tried to get a tile outside the chunk!")
     }
+
+    pub fn set_tile(&mut self, pos: &LocalPosition, tile: TileType) {
+        let row = self
+            .tile_types
+            .get_mut(pos.y as usize)
+            .expect("Tried to set a tile outside the chunk!");
+        *row.get_mut(pos.x as usize)
+            .expect("Tried to set a tile outside the chunk!") = tile;
+    }
 }
 
 impl<'a> IntoIterator for &'a ChunkTerrain {
@@ -44,7 +53,7 @@ impl<'a> IntoIterator for &'a ChunkTerrain {
     }
 }
 
-/// Iterator for the chunk. Iterates on the corresponding `TileType`, `Position` and `Brightness`.
+/// Iterator for the chunk. Iterates on the corresponding `TileType` and `Position`.
 pub struct ChunkIterator<'a> {
     chunk: ChunkPosition,
     tile_types: &'a Vec<Vec<TileType>>,
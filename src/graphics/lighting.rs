@@ -0,0 +1,124 @@
+use macroquad::color::Color;
+
+use crate::coords::distance;
+use crate::fov::FieldOfView;
+use crate::tile_types::TileType;
+
+use std::collections::{HashMap, HashSet};
+
+/// How much light a tile needs to be seen at all.
+const VISIBLE_LIGHT: f32 = 0.02;
+
+/// The color and the strength of the light, every channel goes from 0 to 1 and more.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct LightColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl LightColor {
+    pub const WHITE: Self = Self::new(1., 1., 1.);
+
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn scaled(self, factor: f32) -> Self {
+        Self::new(self.r * factor, self.g * factor, self.b * factor)
+    }
+
+    /// The strongest of the channels.
+    pub fn intensity(self) -> f32 {
+        self.r.max(self.g).max(self.b)
+    }
+}
+
+impl std::ops::AddAssign for LightColor {
+    fn add_assign(&mut self, other: Self) {
+        self.r += other.r;
+        self.g += other.g;
+        self.b += other.b;
+    }
+}
+
+impl From<LightColor> for Color {
+    fn from(val: LightColor) -> Self {
+        Self::new(val.r.min(1.), val.g.min(1.), val.b.min(1.), 1.)
+    }
+}
+
+/// Something that shines on the tiles around it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Light {
+    pub radius: i16,
+    pub color: LightColor,
+}
+
+impl Light {
+    pub const fn new(radius: i16, color: LightColor) -> Self {
+        Self { radius, color }
+    }
+
+    /// The light given off by the tiles and entities that glow.
+    pub const fn emitted_by(tile: TileType) -> Option<Self> {
+        match tile {
+            TileType::WallTorch => Some(Self::new(7, LightColor::new(1., 0.7, 0.35))),
+            TileType::MushroomOrange => Some(Self::new(2, LightColor::new(0.6, 0.35, 0.1))),
+            TileType::Lava => Some(Self::new(3, LightColor::new(0.9, 0.3, 0.05))),
+            TileType::MoonFlower => Some(Self::new(2, LightColor::new(0.2, 0.3, 0.6))),
+            _ => None,
+        }
+    }
+}
+
+/// How much light falls on every tile around the player.
+/// Positions are the absolute tile coordinates.
+#[derive(Debug, Default)]
+pub struct LightMap {
+    tiles: HashMap<(i32, i32), LightColor>,
+    /// The light that falls everywhere, like the sunlight.
+    ambient: LightColor,
+}
+
+impl LightMap {
+    /// Adds up the light from all the `lights` on top of the `ambient` light,
+    /// `blockers` cast the shadows. The light fades out with the distance from the source.
+    pub fn compute(
+        &mut self,
+        lights: &[((i32, i32), Light)],
+        blockers: &HashSet<(i32, i32)>,
+        ambient: LightColor,
+    ) {
+        self.tiles.clear();
+        self.ambient = ambient;
+        let mut fov = FieldOfView::default();
+        for (origin, light) in lights {
+            fov.compute(*origin, light.radius, |position| {
+                blockers.contains(&position)
+            });
+            let reach = f32::from(light.radius) + 1.;
+            for position in fov.iter() {
+                let dist = distance(
+                    (origin.0 as f32, origin.1 as f32),
+                    (position.0 as f32, position.1 as f32),
+                );
+                let falloff = (1. - dist / reach).max(0.);
+                *self.tiles.entry(*position).or_default() += light.color.scaled(falloff);
+            }
+        }
+    }
+
+    pub fn get(&self, position: (i32, i32)) -> LightColor {
+        let mut light = self.ambient;
+        if let Some(tile_light) = self.tiles.get(&position) {
+            light += *tile_light;
+        }
+        light
+    }
+
+    /// Is the tile lit enough to be seen?
+    pub fn is_lit(&self, position: (i32, i32)) -> bool {
+        self.get(position).intensity() > VISIBLE_LIGHT
+    }
+}
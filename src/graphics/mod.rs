@@ -1,3 +1,3 @@
-pub mod brightness;
 pub mod chunk_terrain;
+pub mod lighting;
 pub mod tile_atlas;
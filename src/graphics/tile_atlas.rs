@@ -1,10 +1,9 @@
 use macroquad::prelude::*;
 
-use crate::coords::distance;
 use crate::entities::entities::Entity;
-use crate::entities::player::Player;
-use crate::graphics::brightness::Brightness;
+use crate::fov::FieldOfView;
 use crate::graphics::chunk_terrain::ChunkTerrain;
+use crate::graphics::lighting::{LightColor, LightMap};
 use crate::tile_types::TileType;
 
 /// Is used to split one `Texture2D` into different tiles.
@@ -34,24 +33,25 @@ impl TileAtlas {
         }
     }
 
-    /// Draws the provided `&Entity`.
-    pub fn draw_entity(&self, entity: &Entity, brightness: Brightness) {
+    /// Draws the provided `&Entity`, colored by the light falling on it.
+    pub fn draw_entity(&self, entity: &Entity, light: LightColor) {
         let params = self.get_texture_params(entity.tile);
         let (x, y) = entity.get_absolute_position_f32();
-        draw_texture_ex(&self.texture, x, y, Color::from(brightness), params);
+        let color = Self::tint(entity.tile, Color::from(light));
+        draw_texture_ex(&self.texture, x, y, color, params);
     }
 
-    /// Draws every tile from the provided `&ChunkTerrain`.
-    pub fn draw_layer(&self, chunk: &ChunkTerrain, player: &Player) {
+    /// Draws every visible and lit tile from the provided `&ChunkTerrain`.
+    pub fn draw_layer(&self, chunk: &ChunkTerrain, fov: &FieldOfView, light_map: &LightMap) {
         for (tile_type, tile_pos) in chunk {
-            let (x, y) = tile_pos.into();
-
-            let dist = distance(player.entity.get_absolute_position_f32(), (x, y));
-            if dist < player.vision_range.into() {
-                let brightness = player.calc_brightness(dist);
-                let params = self.get_texture_params(tile_type);
-                draw_texture_ex(&self.texture, x, y, Color::from(brightness), params);
+            let position = tile_pos.get_absolute_position_i32();
+            if !fov.is_visible(position) || !light_map.is_lit(position) {
+                continue;
             }
+            let (x, y) = tile_pos.into();
+            let params = self.get_texture_params(tile_type);
+            let color = Self::tint(tile_type, Color::from(light_map.get(position)));
+            draw_texture_ex(&self.texture, x, y, color, params);
         }
     }
 
@@ -100,6 +100,30 @@ impl TileAtlas {
             TileType::TreeStomp => (3., 4.),
             TileType::VioletFlower => (0., 5.),
             TileType::MushroomRed => (1., 5.),
+            TileType::Rat => (3., 5.),
+            TileType::Slime => (0., 6.),
+            TileType::Bag => (1., 6.),
+            TileType::OpenDoor => (0., 1.),
+            TileType::LockedDoor => (0., 1.),
+            TileType::WallTorch => (1., 3.),
+            TileType::Lava => (0., 3.),
+            TileType::Ghost => (2., 5.),
+            TileType::MoonFlower => (0., 5.),
+            TileType::Puddle => (3., 2.),
         }
     }
+
+    /// Tiles without their own sprite reuse another one, tinted with a color.
+    fn tint(tile_type: TileType, color: Color) -> Color {
+        let (r, g, b, a) = match tile_type {
+            TileType::OpenDoor => (0.6, 0.6, 0.6, 0.35),
+            TileType::LockedDoor => (1., 0.6, 0.5, 1.),
+            TileType::WallTorch => (1., 0.85, 0.6, 1.),
+            TileType::Lava => (1., 0.35, 0.1, 1.),
+            TileType::MoonFlower => (0.6, 0.8, 1., 1.),
+            TileType::Puddle => (0.8, 0.9, 1., 0.6),
+            _ => (1., 1., 1., 1.),
+        };
+        Color::new(color.r * r, color.g * g, color.b * b, color.a * a)
+    }
 }
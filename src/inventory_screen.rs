@@ -0,0 +1,155 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::entities::equipment::EquipmentSlot;
+use crate::entities::inventory::{MAX_SLOTS, MAX_WEIGHT};
+use crate::entities::player::{Action, Player};
+
+const LINE_HEIGHT: f32 = 26.;
+const WIDTH: f32 = 520.;
+
+/// The screen listing the items the player carries and wears.
+/// The cursor goes over the inventory slots first and then over the equipment slots.
+#[derive(Debug, Default)]
+pub struct InventoryScreen {
+    pub open: bool,
+    /// The line under the cursor.
+    selected: usize,
+}
+
+impl InventoryScreen {
+    /// Toggles the screen and, while it is open, picks what to do with the items.
+    /// Returns the action for the player to take.
+    pub fn handle_input(&mut self, player: &Player, bindings: &KeyBindings) -> Option<Action> {
+        if bindings.pressed(Command::Inventory) {
+            self.open = !self.open;
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+        if bindings.pressed(Command::Cancel) {
+            self.open = false;
+            return None;
+        }
+
+        let items = player.inventory.items().len();
+        let lines = items + EquipmentSlot::ALL.len();
+        // The cursor moves with the keys that move the player north and south.
+        if bindings.pressed(Command::MoveNorth) {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if bindings.pressed(Command::MoveSouth) {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(lines - 1);
+
+        let use_pressed = bindings.pressed(Command::Confirm);
+        if self.selected >= items {
+            let slot = EquipmentSlot::ALL[self.selected - items];
+            if use_pressed && player.equipment.get(slot).is_some() {
+                return Some(Action::Unequip(slot));
+            }
+            None
+        } else if use_pressed {
+            Some(Action::UseItem(self.selected))
+        } else if bindings.pressed(Command::Drop) {
+            Some(Action::DropItem(self.selected))
+        } else {
+            None
+        }
+    }
+
+    /// Draws the items, the equipment and the stats over the screen.
+    pub fn draw(&self, player: &Player, bindings: &KeyBindings) {
+        let inventory = &player.inventory;
+        let (x, y) = (screen_width() / 2. - WIDTH / 2., 60.);
+        let lines = inventory.items().len().max(1) + EquipmentSlot::ALL.len() + 7;
+        draw_rectangle(
+            x,
+            y,
+            WIDTH,
+            LINE_HEIGHT * lines as f32,
+            Color::new(0., 0., 0., 0.85),
+        );
+        let mut line = 1.;
+        let mut write = |text: &str, color: Color| {
+            draw_text(text, x + 10., y + LINE_HEIGHT * line, LINE_HEIGHT, color);
+            line += 1.;
+        };
+
+        write(
+            format!(
+                "Inventory  {}/{} slots  weight {}/{}",
+                inventory.items().len(),
+                MAX_SLOTS,
+                inventory.weight(),
+                MAX_WEIGHT
+            )
+            .as_str(),
+            WHITE,
+        );
+        for (index, item) in inventory.items().iter().enumerate() {
+            write(
+                self.entry(index, item.to_string()).as_str(),
+                self.color(index),
+            );
+        }
+        if inventory.items().is_empty() {
+            write("  You don't carry anything.", GRAY);
+        }
+
+        write("", WHITE);
+        write("Equipment", WHITE);
+        let items = inventory.items().len();
+        for (index, slot) in EquipmentSlot::ALL.iter().enumerate() {
+            let equipped = player
+                .equipment
+                .get(*slot)
+                .map_or("-".to_string(), |kind| kind.to_string());
+            write(
+                self.entry(items + index, format!("{}: {}", slot, equipped))
+                    .as_str(),
+                self.color(items + index),
+            );
+        }
+
+        let stats = player.combat_stats();
+        write("", WHITE);
+        write(
+            format!(
+                "Attack {}  Defense {}  Vision {}",
+                stats.attack,
+                stats.defense,
+                player.vision()
+            )
+            .as_str(),
+            WHITE,
+        );
+        write(
+            format!(
+                "{}/{} select, {} use/eat/equip/take off, {} drop, {} close",
+                bindings.key_name(Command::MoveNorth),
+                bindings.key_name(Command::MoveSouth),
+                bindings.key_name(Command::Confirm),
+                bindings.key_name(Command::Drop),
+                bindings.key_name(Command::Inventory)
+            )
+            .as_str(),
+            GRAY,
+        );
+    }
+
+    fn entry(&self, index: usize, text: String) -> String {
+        let marker = if index == self.selected { ">" } else { " " };
+        format!("{} {}", marker, text)
+    }
+
+    fn color(&self, index: usize) -> Color {
+        if index == self.selected {
+            YELLOW
+        } else {
+            LIGHTGRAY
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::dialogue_screen::wrap;
+use crate::entities::player::Player;
+use crate::entities::quests::Journal;
+
+const LINE_HEIGHT: f32 = 24.;
+const WIDTH: f32 = 600.;
+
+/// The screen listing the quests the player took on
+/// and, on the other page, the writings read on the engraved stones.
+#[derive(Debug, Default)]
+pub struct JournalScreen {
+    pub open: bool,
+    /// Is the lore page shown instead of the quests?
+    show_lore: bool,
+}
+
+impl JournalScreen {
+    /// Toggles the screen and switches the pages.
+    pub fn handle_input(&mut self, bindings: &KeyBindings) {
+        if bindings.pressed(Command::Journal) {
+            self.open = !self.open;
+        }
+        if !self.open {
+            return;
+        }
+        if bindings.pressed(Command::Cancel) {
+            self.open = false;
+        }
+        // The pages are turned with the keys that move the player west and east.
+        if bindings.pressed(Command::MoveWest) || bindings.pressed(Command::MoveEast) {
+            self.show_lore = !self.show_lore;
+        }
+    }
+
+    /// Draws the page of the journal over the screen.
+    pub fn draw(&self, player: &Player, bindings: &KeyBindings) {
+        let mut text = if self.show_lore {
+            Self::lore_page(&player.lore)
+        } else {
+            Self::quest_page(&player.journal)
+        };
+        text.push((
+            format!(
+                "{}/{} switch page, {} close",
+                bindings.key_name(Command::MoveWest),
+                bindings.key_name(Command::MoveEast),
+                bindings.key_name(Command::Journal)
+            ),
+            GRAY,
+        ));
+
+        let (x, y) = (screen_width() / 2. - WIDTH / 2., 60.);
+        draw_rectangle(
+            x,
+            y,
+            WIDTH,
+            LINE_HEIGHT * (text.len() + 1) as f32,
+            Color::new(0., 0., 0., 0.85),
+        );
+        for (line, (text, color)) in text.iter().enumerate() {
+            draw_text(
+                text,
+                x + 10.,
+                y + LINE_HEIGHT * (line + 1) as f32,
+                LINE_HEIGHT,
+                *color,
+            );
+        }
+    }
+
+    /// The active quests with their progress and the finished ones below.
+    fn quest_page(journal: &Journal) -> Vec<(String, Color)> {
+        let mut text: Vec<(String, Color)> = vec![("Quests".to_string(), WHITE)];
+        for quest in journal.active() {
+            text.push((
+                format!("{} ({})", quest.title, quest.progress_text()),
+                YELLOW,
+            ));
+            for line in wrap(&quest.description, WIDTH - 40., LINE_HEIGHT) {
+                text.push((format!("  {}", line), LIGHTGRAY));
+            }
+            text.push((format!("  Reward: {}", quest.reward), LIGHTGRAY));
+        }
+        if journal.active().next().is_none() {
+            text.push(("  You don't have any quests.".to_string(), GRAY));
+        }
+        let completed: Vec<_> = journal.quests.iter().filter(|q| q.completed).collect();
+        if !completed.is_empty() {
+            text.push((String::new(), WHITE));
+            text.push(("Completed".to_string(), WHITE));
+            for quest in completed {
+                text.push((format!("  {}", quest.title), GRAY));
+            }
+        }
+        text
+    }
+
+    /// The writings from the engraved stones, in the order they were read.
+    fn lore_page(lore: &[String]) -> Vec<(String, Color)> {
+        let mut text: Vec<(String, Color)> = vec![("Lore".to_string(), WHITE)];
+        for writing in lore {
+            for (index, line) in wrap(writing, WIDTH - 40., LINE_HEIGHT)
+                .into_iter()
+                .enumerate()
+            {
+                let marker = if index == 0 { "-" } else { " " };
+                text.push((format!("{} {}", marker, line), LIGHTGRAY));
+            }
+        }
+        if lore.is_empty() {
+            text.push(("  You haven't read any engravings yet.".to_string(), GRAY));
+        }
+        text
+    }
+}
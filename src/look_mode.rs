@@ -0,0 +1,145 @@
+use macroquad::prelude::*;
+
+use crate::camera::tile_at;
+use crate::controls::{Command, KeyBindings};
+use crate::entities::entities::Entities;
+use crate::graphics::lighting::LightMap;
+use crate::tooltip::describe_tile;
+use crate::world::World;
+
+/// How many tiles the cursor jumps while the run key is held.
+const FAST_STEP: i32 = 8;
+
+/// Examining the world. The camera leaves the player and follows a cursor
+/// that is moved with the direction keys or the mouse.
+#[derive(Debug, Default)]
+pub struct LookMode {
+    pub active: bool,
+    /// The examined tile, in the absolute tile coordinates.
+    cursor: (i32, i32),
+    /// Where the mouse was on the screen, the cursor only jumps to it when it moves.
+    last_mouse: Vec2,
+    /// Does the camera follow the cursor? Not while the mouse moves it.
+    follow: bool,
+}
+
+impl LookMode {
+    /// Starts looking around from the player's tile.
+    pub fn start(&mut self, player: (i32, i32)) {
+        self.active = true;
+        self.cursor = player;
+        self.last_mouse = mouse_position().into();
+        self.follow = true;
+    }
+
+    /// The center of the examined tile for the camera to glide to.
+    pub fn follows(&self) -> Option<Vec2> {
+        self.follow
+            .then(|| Vec2::new(self.cursor.0 as f32, self.cursor.1 as f32))
+    }
+
+    /// Moves the cursor, `mouse` is the mouse position in the world.
+    /// Returns false when the player is done looking.
+    pub fn handle_input(&mut self, bindings: &KeyBindings, mouse: Vec2) -> bool {
+        if bindings.pressed(Command::Cancel) || bindings.pressed(Command::Look) {
+            self.active = false;
+            return false;
+        }
+        let step = if bindings.down(Command::Run) {
+            FAST_STEP
+        } else {
+            1
+        };
+        for command in [
+            Command::MoveNorth,
+            Command::MoveSouth,
+            Command::MoveWest,
+            Command::MoveEast,
+            Command::MoveNorthWest,
+            Command::MoveNorthEast,
+            Command::MoveSouthWest,
+            Command::MoveSouthEast,
+        ] {
+            if let Some((right, up)) = command.direction() {
+                if bindings.pressed(command) {
+                    self.cursor.0 += i32::from(right) * step;
+                    self.cursor.1 += i32::from(up) * step;
+                    self.follow = true;
+                }
+            }
+        }
+        // The camera is moved by hand, so it stops following the cursor.
+        if [
+            Command::PanNorth,
+            Command::PanSouth,
+            Command::PanWest,
+            Command::PanEast,
+        ]
+        .iter()
+        .any(|command| bindings.down(*command))
+        {
+            self.follow = false;
+        }
+        let on_screen: Vec2 = mouse_position().into();
+        if on_screen.distance(self.last_mouse) > 0.5 {
+            self.cursor = tile_at(mouse);
+            self.follow = false;
+        }
+        self.last_mouse = on_screen;
+        true
+    }
+
+    /// Draws the frame around the examined tile, in the camera space.
+    pub fn draw_cursor(&self) {
+        let (x, y) = (self.cursor.0 as f32, self.cursor.1 as f32);
+        draw_rectangle_lines(x - 1., y, 1., 1., 0.08, YELLOW);
+    }
+
+    /// What the player knows about the examined tile.
+    pub fn describe(
+        &self,
+        world: &World,
+        entities: &Entities,
+        light_map: &LightMap,
+    ) -> Vec<String> {
+        let position = self.cursor;
+        if !entities.fov.is_visible(position) {
+            return match entities.memory.get(position) {
+                Some(remembered) => {
+                    let turns = entities.clock.turn.saturating_sub(remembered.turn);
+                    let mut lines = vec![format!(
+                        "You remember {} here, {} turns ago.",
+                        remembered.terrain, turns
+                    )];
+                    lines.extend(remembered.entities.iter().map(|tile| format!("  {}", tile)));
+                    lines
+                }
+                None => vec!["You haven't seen this place.".to_string()],
+            };
+        }
+        describe_tile(world, entities, light_map, position)
+    }
+
+    /// Draws the description of the examined tile at the bottom of the screen.
+    pub fn draw_panel(lines: &[String], bindings: &KeyBindings) {
+        let height = 40. + lines.len() as f32 * 24.;
+        let (x, y) = (5., screen_height() - height - 5.);
+        draw_rectangle(x, y, 420., height, Color::new(0., 0., 0., 0.7));
+        draw_text(
+            format!("Looking ({} to stop)", bindings.key_name(Command::Cancel)).as_str(),
+            x + 10.,
+            y + 24.,
+            22.,
+            YELLOW,
+        );
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(
+                line.as_str(),
+                x + 10.,
+                y + 50. + index as f32 * 24.,
+                22.,
+                WHITE,
+            );
+        }
+    }
+}
@@ -1,4 +1,3 @@
-use macroquad::camera::Camera2D;
 use macroquad::prelude::*;
 
 mod graphics;
@@ -7,209 +6,207 @@ use graphics::tile_atlas::TileAtlas;
 mod tile_types;
 
 mod coords;
-use coords::{get_checked_position, AbsolutePosition, ChunkPosition, LocalPosition, CHUNK_SIZE};
 
 mod world;
-use world::World;
 
 mod generator;
-use generator::Generator;
 
 mod entities;
-use entities::entities::Entities;
+use entities::crafting::{parse_recipes, RECIPES_PATH};
+use entities::dialogue::{Dialogues, DIALOGUE_PATH};
+use entities::lore::{Lore, LORE_PATH};
+use entities::quests::{QuestBook, QUESTS_PATH};
+
+mod clock;
+
+mod fov;
+
+mod map_memory;
+
+mod look_mode;
+
+mod tooltip;
+
+mod minimap;
 
 mod camera;
-use camera::{mouse_position_relative_to, Camera};
 
-#[macroquad::main("kiriRoguelike")]
-async fn main() {
-    // Load tile atlas from the default file.
-    let tile_atlas = TileAtlas::default().await;
+mod controls;
+use controls::Command;
 
-    // Create the world, place that holds terrain.
-    let mut world = World::default();
-    // Create and seed the generator.
-    let generator = Generator::new(0);
-    // Create the container for all of the entities.
-    let mut entities = Entities::default();
-    // Just a number to show the score.
-    let mut score: i64;
+mod message_log;
 
-    // Create main camera.
-    let mut main_camera = Camera::default();
+mod inventory_screen;
 
-    // We need to save the state of the mouse button
-    // to detect mouse clicks, as opposed to just "is pressed" state.
-    let mut left_mouse_pressed = false;
+mod crafting_screen;
 
-    // The position of the tile we clicked.
-    let mut cursor;
+mod dialogue_screen;
 
-    // The infinite game loop.
-    loop {
-        // ===========Input===========
-        // Get the Vec2 position of the mouse inside the game world.
-        let mouse_position = mouse_position_relative_to(&main_camera);
-
-        // Select the clicked on tile and update the left mouse button state.
-        let (updated_left_mouse_pressed, updated_cursor) =
-            handle_mouse(left_mouse_pressed, mouse_position);
-        left_mouse_pressed = updated_left_mouse_pressed;
-        cursor = updated_cursor;
-
-        // Print the info about the tile we clicked.
-        if let Some(cursor) = cursor {
-            println!("Terrain: {}", world.get_tile(&cursor).unwrap());
-            if let Some(entity) = entities.get_mut_entity_at_pos(&cursor) {
-                println!("{}", entity);
-                entity.removed = true;
-            }
-        }
+mod journal_screen;
 
-        // Entities container handles player movement.
-        entities.input(handle_keyboard(&mut main_camera));
-        // ===========Update===========
-
-        // We need to know the player's chunk to see what chunks need to be
-        // loaded or unloaded.
-        let player_pos = entities.player.entity.chunk_pos;
-        // Load or generate the chunks near the player.
-        world.update(&player_pos, &generator);
-
-        // Load, generate or update all of the entities.
-        entities.update(&world, &generator);
-        // We need to get the new score to show it on screen.
-        score = entities.player.score;
-
-        // Point the camera to the new position of the player before the drawing stage.
-        main_camera.set_target(entities.player.entity.get_absolute_position_f32().into());
-
-        // ===========Draw===========
-        // Fill the canvas with the background color.
-        clear_background(Color {
-            r: 40.0 / 255.0,
-            g: 40.0 / 255.0,
-            b: 40.0 / 255.0,
-            a: 1.0,
-        });
-
-        // --- Camera space, render game objects.
-        let (target, zoom) = main_camera.get();
-        let zoom = Vec2::new(zoom.x, -zoom.y);
-        set_camera(&Camera2D {
-            target,
-            zoom,
-            ..Camera2D::default()
-        });
-
-        // We draw everything besides the ui in camera space.
-        // World needs to know the players location to know what terrain is visible
-        // and how far it is to make it less visible.
-        let player = &entities.player;
-        world.draw(&tile_atlas, player);
-        // Entities container already knows about the player.
-        entities.draw(&tile_atlas);
-
-        // Draw the mouse cursor. As a small circle.
-        draw_circle(
-            mouse_position.x,
-            mouse_position.y,
-            0.1,
-            Color::from_rgba(100, 75, 120, 255),
-        );
-
-        // --- Fixed screen space, render ui.
-        set_default_camera();
-        draw_text(
-            format!("Collect coins! you have {}.", score).as_str(),
-            5.,
-            5.,
-            30.,
-            Color::new(40., 80., 170., 200.),
-        );
+mod rng;
 
-        next_frame().await
-    }
-}
+mod achievements;
 
-/// Handle the input from the keyboard.
-/// Returns the direction for the player to set the destination to.
-fn handle_keyboard(camera: &mut Camera) -> (i8, i8) {
-    camera.scroll(0.03, 0.97);
-    let mut res = (0, 0);
-    if is_key_pressed(KeyCode::Right) {
-        res.0 = 1;
-    }
-    if is_key_pressed(KeyCode::Left) {
-        res.0 = -1;
-    }
-    if is_key_pressed(KeyCode::Down) {
-        res.1 = -1;
-    }
-    if is_key_pressed(KeyCode::Up) {
-        res.1 = 1;
-    }
+mod events;
 
-    res
-}
+mod save;
 
-/// Handle the mouse. Print the coordinates where the mouse was clicked.
-/// Return the absolute position to be able to see what was clicked.
-fn handle_mouse(
-    left_mouse_pressed: bool,
-    mouse_position: Vec2,
-) -> (bool, Option<AbsolutePosition>) {
-    if is_mouse_button_down(MouseButton::Left) {
-        let (mut mouse_x, mut mouse_y) = (mouse_position.x, mouse_position.y);
-        mouse_x = mouse_x.floor();
-        mouse_y = mouse_y.floor();
-        let chunk_dimensions = f32::from(CHUNK_SIZE);
-        let (world_x, world_y) = (
-            (mouse_x / chunk_dimensions).floor(),
-            (mouse_y / chunk_dimensions).floor(),
-        );
-
-        if mouse_x < 0.0 {
-            mouse_x = chunk_dimensions - (-mouse_x % chunk_dimensions);
-        }
-        if mouse_y < 0.0 {
-            mouse_y = chunk_dimensions - (-mouse_y % chunk_dimensions);
-        }
+mod score;
+
+mod settings;
+use settings::Settings;
+
+mod statistics;
+
+mod weather;
+
+mod game;
+use game::{Game, GameData, GameState};
+
+mod menu;
+use menu::Menu;
 
-        if !left_mouse_pressed {
-            let (x, y) = (
-                mouse_x.abs() % chunk_dimensions,
-                mouse_y.abs() % chunk_dimensions,
-            );
-            debug!(
-                "World {world_x:.0}:{world_y:.0} | Position {x:.0}:{y:.0}",
-                world_x = world_x,
-                x = x,
-                world_y = world_y,
-                y = y
-            );
-            let AbsolutePosition {
-                chunk: chunk_pos,
-                local: local_pos,
-            } = get_checked_position(
-                ChunkPosition {
-                    x: world_x as i32,
-                    y: world_y as i32,
-                },
-                LocalPosition {
-                    x: (x + 1.) as i16,
-                    y: y as i16,
-                },
-            );
-            return (
-                true,
-                Some(AbsolutePosition {
-                    chunk: chunk_pos,
-                    local: local_pos,
-                }),
-            );
+mod title_screen;
+use title_screen::{TitleChoice, TitleScreen};
+
+mod settings_screen;
+use settings_screen::SettingsScreen;
+
+#[macroquad::main("kiriRoguelike")]
+async fn main() {
+    // Load tile atlas from the default file.
+    let tile_atlas = TileAtlas::default().await;
+
+    // The quests, the lore, the recipes and the conversations, loaded from the data files.
+    let quests = load_string(QUESTS_PATH).await.unwrap();
+    let lore = load_string(LORE_PATH).await.unwrap();
+    let recipes = load_string(RECIPES_PATH).await.unwrap();
+    let dialogues = load_string(DIALOGUE_PATH).await.unwrap();
+    let data = GameData {
+        quest_book: QuestBook::parse(&quests).expect("The quests file is broken!"),
+        lore: Lore::parse(&lore).expect("The lore file is broken!"),
+        recipes: parse_recipes(&recipes).expect("The recipes file is broken!"),
+        dialogues: Dialogues::parse(&dialogues).expect("The dialogue file is broken!"),
+    };
+    let mut settings = Settings::load();
+
+    let mut state = GameState::Title;
+    // There is no game until one is started from the title screen.
+    let mut game: Option<Game> = None;
+    let mut title_screen = TitleScreen::default();
+    let mut settings_screen = SettingsScreen::default();
+    let mut pause_menu = Menu::new(vec![
+        "Resume",
+        "Save game",
+        "Load game",
+        "Settings",
+        "Quit to title",
+    ]);
+    // The settings can be opened from the title and the pause menus, and go back to them.
+    let mut settings_opened_from = GameState::Title;
+
+    // The infinite game loop.
+    loop {
+        match state {
+            GameState::Title => {
+                match title_screen.handle_input(&settings.bindings) {
+                    Some(TitleChoice::NewGame(seed)) => {
+                        game = Some(Game::new(seed, &data));
+                        state = GameState::Playing;
+                    }
+                    Some(TitleChoice::Continue) => match Game::load(&data) {
+                        Ok(Some(loaded)) => {
+                            game = Some(loaded);
+                            state = GameState::Playing;
+                        }
+                        Ok(None) => title_screen.notice = Some("The save file is broken.".into()),
+                        Err(_) => title_screen.notice = Some("There is no saved game.".into()),
+                    },
+                    Some(TitleChoice::Settings) => {
+                        settings_opened_from = GameState::Title;
+                        state = GameState::Settings;
+                    }
+                    Some(TitleChoice::Quit) => break,
+                    None => {}
+                }
+                title_screen.draw(&settings.bindings);
+            }
+            GameState::Settings => {
+                if settings_screen.handle_input(&mut settings) {
+                    // The error is shown where the settings go back to.
+                    if let Err(error) = settings.write() {
+                        let notice = format!("Can't save the settings: {}", error);
+                        match (settings_opened_from, game.as_mut()) {
+                            (GameState::Paused, Some(game)) => game.warn(notice),
+                            _ => title_screen.notice = Some(notice),
+                        }
+                    }
+                    state = settings_opened_from;
+                }
+                settings_screen.draw(&settings);
+            }
+            GameState::Playing => {
+                let game = game
+                    .as_mut()
+                    .expect("The game is started before it's played!");
+                // Cancelling closes the open screens first, then pauses the game.
+                if settings.bindings.pressed(Command::Cancel) && !game.screen_open() {
+                    state = GameState::Paused;
+                } else {
+                    game.update(&data, &settings);
+                }
+                if game.entities.player.is_dead() {
+                    state = GameState::GameOver;
+                }
+                game.draw(&tile_atlas, &settings.bindings);
+            }
+            GameState::Paused => {
+                let game = game.as_mut().expect("Only a started game can be paused!");
+                let choice = if settings.bindings.pressed(Command::Cancel) {
+                    Some(0)
+                } else {
+                    pause_menu.handle_input(&settings.bindings)
+                };
+                match choice {
+                    Some(0) => state = GameState::Playing,
+                    Some(1) => {
+                        game.save();
+                        state = GameState::Playing;
+                    }
+                    Some(2) => {
+                        game.reload(&data);
+                        state = GameState::Playing;
+                    }
+                    Some(3) => {
+                        settings_opened_from = GameState::Paused;
+                        state = GameState::Settings;
+                    }
+                    Some(_) => state = GameState::Title,
+                    None => {}
+                }
+                game.draw(&tile_atlas, &settings.bindings);
+                draw_rectangle(
+                    0.,
+                    0.,
+                    screen_width(),
+                    screen_height(),
+                    Color::new(0., 0., 0., 0.6),
+                );
+                pause_menu.draw("Paused");
+            }
+            GameState::GameOver => {
+                let game = game.as_ref().expect("Only a started game can be over!");
+                game.draw_game_over(&settings.bindings);
+                if settings.bindings.pressed(Command::Confirm) {
+                    state = GameState::Title;
+                }
+                if settings.bindings.pressed(Command::Cancel) {
+                    break;
+                }
+            }
         }
-        return (true, None);
+
+        next_frame().await
     }
-    (false, None)
 }
@@ -0,0 +1,85 @@
+use crate::coords::CHUNK_SIZE;
+use crate::save::{parse_variant, SaveFile};
+use crate::tile_types::TileType;
+
+use std::collections::HashMap;
+
+/// What the player saw on the tile the last time it was in view.
+#[derive(Debug, Clone)]
+pub struct Remembered {
+    pub terrain: TileType,
+    pub entities: Vec<TileType>,
+    /// The turn the tile was last seen on.
+    pub turn: u64,
+}
+
+/// Every tile the player has seen, as it was when it was last seen.
+/// Positions are the absolute tile coordinates.
+#[derive(Debug, Default)]
+pub struct MapMemory {
+    tiles: HashMap<(i32, i32), Remembered>,
+    /// Grows every time a tile in the chunk looks different than it was remembered,
+    /// so the map knows which chunks to redraw.
+    revisions: HashMap<(i32, i32), u64>,
+}
+
+impl MapMemory {
+    pub fn remember(&mut self, position: (i32, i32), remembered: Remembered) {
+        let changed = self.tiles.get(&position).is_none_or(|old| {
+            old.terrain != remembered.terrain || old.entities != remembered.entities
+        });
+        if changed {
+            *self.revisions.entry(chunk_of(position)).or_default() += 1;
+        }
+        self.tiles.insert(position, remembered);
+    }
+
+    pub fn get(&self, position: (i32, i32)) -> Option<&Remembered> {
+        self.tiles.get(&position)
+    }
+
+    /// The revision of the chunk, None if nothing in it was seen yet.
+    pub fn chunk_revision(&self, chunk: (i32, i32)) -> Option<u64> {
+        self.revisions.get(&chunk).copied()
+    }
+
+    /// Writes down every remembered tile as `x,y,turn,terrain,entities...`.
+    pub fn save(&self, file: &mut SaveFile) {
+        for ((x, y), remembered) in &self.tiles {
+            let mut line = format!("{},{},{},{:?}", x, y, remembered.turn, remembered.terrain);
+            for entity in &remembered.entities {
+                line.push_str(format!(",{:?}", entity).as_str());
+            }
+            file.push("seen", line);
+        }
+    }
+
+    /// Restores the tiles written down by `save`.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let mut memory = Self::default();
+        for value in file.get_all("seen") {
+            let mut parts = value.split(',');
+            let position = (parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+            let turn = parts.next()?.parse().ok()?;
+            let terrain = parse_variant(&TileType::ALL, parts.next()?)?;
+            let entities = parts
+                .map(|name| parse_variant(&TileType::ALL, name))
+                .collect::<Option<_>>()?;
+            memory.remember(
+                position,
+                Remembered {
+                    terrain,
+                    entities,
+                    turn,
+                },
+            );
+        }
+        Some(memory)
+    }
+}
+
+/// The chunk the absolute tile position is in.
+pub fn chunk_of((x, y): (i32, i32)) -> (i32, i32) {
+    let size = i32::from(CHUNK_SIZE);
+    (x.div_euclid(size), y.div_euclid(size))
+}
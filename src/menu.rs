@@ -0,0 +1,55 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+
+const LINE_HEIGHT: f32 = 40.;
+
+/// A list of options, moved through with the north and south keys and picked with the confirm key.
+#[derive(Debug)]
+pub struct Menu {
+    options: Vec<&'static str>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(options: Vec<&'static str>) -> Self {
+        Self {
+            options,
+            selected: 0,
+        }
+    }
+
+    /// Moves the selection, returns the index of the confirmed option.
+    pub fn handle_input(&mut self, bindings: &KeyBindings) -> Option<usize> {
+        if bindings.pressed(Command::MoveSouth) {
+            self.selected = (self.selected + 1) % self.options.len();
+        }
+        if bindings.pressed(Command::MoveNorth) {
+            self.selected = (self.selected + self.options.len() - 1) % self.options.len();
+        }
+        bindings.pressed(Command::Confirm).then_some(self.selected)
+    }
+
+    /// Draws the title and the options below it in the middle of the screen.
+    pub fn draw(&self, title: &str) {
+        let x = screen_width() / 2. - 160.;
+        let mut y = screen_height() / 3.;
+        draw_text(title, x, y, LINE_HEIGHT * 1.5, WHITE);
+        y += LINE_HEIGHT;
+        for (index, option) in self.options.iter().enumerate() {
+            y += LINE_HEIGHT;
+            let (marker, color) = if index == self.selected {
+                ("> ", YELLOW)
+            } else {
+                ("  ", LIGHTGRAY)
+            };
+            draw_text(
+                format!("{}{}", marker, option).as_str(),
+                x,
+                y,
+                LINE_HEIGHT,
+                color,
+            );
+        }
+    }
+}
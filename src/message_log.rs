@@ -0,0 +1,198 @@
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::events::{EventBus, EventListener, GameEvent};
+use crate::tile_types::TileType;
+
+/// How many of the latest messages are shown in the panel.
+const VISIBLE_MESSAGES: usize = 6;
+/// How many messages are kept in the history.
+const MAX_HISTORY: usize = 500;
+const LINE_HEIGHT: f32 = 20.;
+const PANEL_WIDTH: f32 = 460.;
+
+/// What the message is about, decides the color of the message.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MessageKind {
+    Info,
+    Pickup,
+    Combat,
+    Discovery,
+    Warning,
+}
+
+impl MessageKind {
+    const fn color(self) -> Color {
+        match self {
+            Self::Info => Color::new(0.85, 0.85, 0.85, 1.),
+            Self::Pickup => Color::new(0.95, 0.8, 0.2, 1.),
+            Self::Combat => Color::new(0.95, 0.45, 0.35, 1.),
+            Self::Discovery => Color::new(0.45, 0.75, 0.95, 1.),
+            Self::Warning => Color::new(1., 0.2, 0.2, 1.),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Message {
+    kind: MessageKind,
+    text: String,
+    /// How many times in a row the same message was added.
+    count: u32,
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.count > 1 {
+            write!(f, "{} x{}", self.text, self.count)
+        } else {
+            write!(f, "{}", self.text)
+        }
+    }
+}
+
+/// Keeps the messages about what happened in the game.
+#[derive(Debug, Default)]
+pub struct MessageLog {
+    messages: Vec<Message>,
+    /// How many messages back from the latest one the panel is scrolled.
+    scroll: usize,
+    /// Is the full history shown instead of the panel?
+    pub show_history: bool,
+}
+
+impl MessageLog {
+    /// Adds the message, repeated messages are merged into one with a counter.
+    pub fn add<S: Into<String>>(&mut self, kind: MessageKind, text: S) {
+        let text = text.into();
+        self.scroll = 0;
+        if let Some(last) = self.messages.last_mut() {
+            if last.kind == kind && last.text == text {
+                last.count += 1;
+                return;
+            }
+        }
+        self.messages.push(Message {
+            kind,
+            text,
+            count: 1,
+        });
+        if self.messages.len() > MAX_HISTORY {
+            self.messages.remove(0);
+        }
+    }
+
+    /// Scrolls the messages and toggles the history view.
+    pub fn handle_input(&mut self, bindings: &KeyBindings) {
+        if bindings.pressed(Command::History) {
+            self.show_history = !self.show_history;
+            self.scroll = 0;
+        }
+        if self.show_history && bindings.pressed(Command::Cancel) {
+            self.show_history = false;
+        }
+        if bindings.pressed(Command::ScrollUp) {
+            self.scroll = (self.scroll + 1).min(self.messages.len().saturating_sub(1));
+        }
+        if bindings.pressed(Command::ScrollDown) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+    }
+
+    /// Draws the panel with the latest messages in screen space, starting from `y`.
+    pub fn draw(&self, x: f32, y: f32, bindings: &KeyBindings) {
+        draw_rectangle(
+            x,
+            y,
+            PANEL_WIDTH,
+            LINE_HEIGHT * VISIBLE_MESSAGES as f32 + 8.,
+            Color::new(0., 0., 0., 0.5),
+        );
+        self.draw_lines(x + 5., y + LINE_HEIGHT, VISIBLE_MESSAGES);
+        if self.scroll > 0 {
+            draw_text(
+                format!(
+                    "[{} newer, {}]",
+                    self.scroll,
+                    bindings.key_name(Command::ScrollDown)
+                )
+                .as_str(),
+                x + PANEL_WIDTH - 170.,
+                y + LINE_HEIGHT,
+                16.,
+                GRAY,
+            );
+        }
+    }
+
+    /// Draws the whole history over the screen.
+    pub fn draw_history(&self, bindings: &KeyBindings) {
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color::new(0., 0., 0., 0.85),
+        );
+        draw_text(
+            format!(
+                "Message history ({}/{} to scroll, {} to close)",
+                bindings.key_name(Command::ScrollUp),
+                bindings.key_name(Command::ScrollDown),
+                bindings.key_name(Command::History)
+            )
+            .as_str(),
+            10.,
+            30.,
+            26.,
+            WHITE,
+        );
+        let lines = ((screen_height() - 60.) / LINE_HEIGHT) as usize;
+        self.draw_lines(10., 60., lines);
+    }
+
+    /// Draws up to `lines` messages ending at the current scroll position.
+    fn draw_lines(&self, x: f32, y: f32, lines: usize) {
+        let end = self.messages.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(lines);
+        for (line, message) in self.messages[start..end].iter().enumerate() {
+            draw_text(
+                message.to_string().as_str(),
+                x,
+                y + line as f32 * LINE_HEIGHT,
+                LINE_HEIGHT,
+                message.kind.color(),
+            );
+        }
+    }
+}
+
+/// Writes down the messages for the player and tells about the events nobody else reports.
+impl EventListener for MessageLog {
+    fn on_event(&mut self, event: &GameEvent, _: &mut EventBus) {
+        match event {
+            GameEvent::EntityDied {
+                tile: TileType::Pengu,
+                ..
+            } => self.add(MessageKind::Warning, "You die..."),
+            GameEvent::Message(kind, text) => self.add(*kind, text.as_str()),
+            GameEvent::QuestCompleted { title, reward } => {
+                self.add(
+                    MessageKind::Discovery,
+                    format!("Quest completed: {}!", title),
+                );
+                self.add(
+                    MessageKind::Pickup,
+                    format!("You get a {} as a reward.", reward),
+                );
+            }
+            GameEvent::AchievementUnlocked(name) => {
+                self.add(
+                    MessageKind::Discovery,
+                    format!("Achievement unlocked: {}!", name),
+                );
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use macroquad::prelude::*;
+
+use std::collections::HashMap;
+
+use crate::controls::{Command, KeyBindings};
+use crate::coords::CHUNK_SIZE;
+use crate::entities::entities::Entities;
+use crate::map_memory::{chunk_of, MapMemory, Remembered};
+use crate::tile_types::TileType;
+
+/// The size of the minimap in the corner, in the screen pixels.
+const MINIMAP_SIZE: f32 = 160.;
+/// How many screen pixels a tile takes on the minimap and on the full-screen map.
+const MINIMAP_TILE: f32 = 2.;
+const FULL_MAP_TILE: f32 = 4.;
+/// How many tiles a second the full-screen map pans.
+const PAN_SPEED: f32 = 40.;
+
+/// The map of the explored world, as a minimap in the corner
+/// or over the whole screen.
+#[derive(Debug, Default)]
+pub struct Minimap {
+    pub full_screen: bool,
+    /// How far the full-screen map is panned from the player, in tiles.
+    offset: Vec2,
+    /// The keys to use the full-screen map with.
+    help: String,
+    /// The explored chunks drawn into textures, a chunk is redrawn when the player learns something new in it.
+    cache: HashMap<(i32, i32), ChunkTexture>,
+}
+
+/// The remembered tiles of a chunk as a texture, a pixel for every tile.
+#[derive(Debug)]
+struct ChunkTexture {
+    image: Image,
+    texture: Texture2D,
+    /// The memory's revision of the chunk the texture was drawn from.
+    revision: u64,
+}
+
+impl ChunkTexture {
+    fn new() -> Self {
+        let size = CHUNK_SIZE;
+        let image = Image::gen_image_color(size, size, BLANK);
+        let texture = Texture2D::from_image(&image);
+        texture.set_filter(FilterMode::Nearest);
+        Self {
+            image,
+            texture,
+            revision: 0,
+        }
+    }
+
+    /// Redraws the pixels of the chunk's tiles from the memory, north is up.
+    fn update(&mut self, memory: &MapMemory, chunk: (i32, i32), revision: u64) {
+        let size = i32::from(CHUNK_SIZE);
+        for y in 0..size {
+            for x in 0..size {
+                let color = memory.get((chunk.0 * size + x, chunk.1 * size + y)).map_or(
+                    BLANK,
+                    |remembered| {
+                        structure_color(remembered)
+                            .unwrap_or_else(|| terrain_color(remembered.terrain))
+                    },
+                );
+                self.image.set_pixel(x as u32, (size - 1 - y) as u32, color);
+            }
+        }
+        self.texture.update(&self.image);
+        self.revision = revision;
+    }
+}
+
+impl Minimap {
+    /// Opens the full-screen map, centered on the player.
+    pub fn open(&mut self, bindings: &KeyBindings) {
+        self.full_screen = true;
+        self.offset = Vec2::ZERO;
+        self.help = format!(
+            "World map ({}/{}/{}/{} to pan, {} or {} to close)",
+            bindings.key_name(Command::PanNorth),
+            bindings.key_name(Command::PanSouth),
+            bindings.key_name(Command::PanWest),
+            bindings.key_name(Command::PanEast),
+            bindings.key_name(Command::Cancel),
+            bindings.key_name(Command::Map),
+        );
+    }
+
+    /// Pans the full-screen map, the map or the cancel command closes it.
+    pub fn handle_input(&mut self, bindings: &KeyBindings) {
+        if bindings.pressed(Command::Cancel) || bindings.pressed(Command::Map) {
+            self.full_screen = false;
+            return;
+        }
+        let speed = PAN_SPEED * get_frame_time();
+        for command in Command::ALL {
+            let direction = match command {
+                Command::PanNorth => Some((0, 1)),
+                Command::PanSouth => Some((0, -1)),
+                Command::PanWest => Some((-1, 0)),
+                Command::PanEast => Some((1, 0)),
+                _ => command.direction(),
+            };
+            if let Some((right, up)) = direction {
+                if bindings.down(command) {
+                    self.offset += vec2(f32::from(right), f32::from(up)) * speed;
+                }
+            }
+        }
+    }
+
+    /// Draws the minimap in the bottom right corner, or the full-screen map when it's open.
+    pub fn draw(&mut self, entities: &Entities) {
+        let player = entities
+            .player
+            .entity
+            .get_absolute_position()
+            .get_absolute_position_i32();
+        if self.full_screen {
+            clear_background(BLACK);
+            let center = (
+                player.0 + self.offset.x.round() as i32,
+                player.1 + self.offset.y.round() as i32,
+            );
+            let area = Rect::new(0., 0., screen_width(), screen_height());
+            self.draw_map(&entities.memory, area, center, FULL_MAP_TILE, player);
+            draw_text(self.help.as_str(), 10., 30., 24., WHITE);
+        } else {
+            let area = Rect::new(
+                screen_width() - MINIMAP_SIZE - 10.,
+                screen_height() - MINIMAP_SIZE - 10.,
+                MINIMAP_SIZE,
+                MINIMAP_SIZE,
+            );
+            draw_rectangle(area.x, area.y, area.w, area.h, Color::new(0., 0., 0., 0.7));
+            self.draw_map(&entities.memory, area, player, MINIMAP_TILE, player);
+            draw_rectangle_lines(area.x, area.y, area.w, area.h, 2., GRAY);
+        }
+    }
+
+    /// Draws the explored tiles around `center` into the area of the screen,
+    /// with the player's marker on top.
+    fn draw_map(
+        &mut self,
+        memory: &MapMemory,
+        area: Rect,
+        center: (i32, i32),
+        tile: f32,
+        player: (i32, i32),
+    ) {
+        let (half_width, half_height) = ((area.w / tile / 2.) as i32, (area.h / tile / 2.) as i32);
+        // North is up, the screen's y grows down.
+        let to_screen = |(x, y): (i32, i32)| {
+            (
+                area.x + (x - center.0 + half_width) as f32 * tile,
+                area.y + (center.1 - y + half_height) as f32 * tile,
+            )
+        };
+        // The tiles that fit in the area.
+        let (left, right) = (center.0 - half_width, center.0 + half_width - 1);
+        let (bottom, top) = (center.1 - half_height + 1, center.1 + half_height);
+        let (first_chunk, last_chunk) = (chunk_of((left, bottom)), chunk_of((right, top)));
+        let size = i32::from(CHUNK_SIZE);
+        for chunk_x in first_chunk.0..=last_chunk.0 {
+            for chunk_y in first_chunk.1..=last_chunk.1 {
+                let chunk = (chunk_x, chunk_y);
+                let revision = match memory.chunk_revision(chunk) {
+                    Some(revision) => revision,
+                    None => continue,
+                };
+                let cache = self.cache.entry(chunk).or_insert_with(ChunkTexture::new);
+                if cache.revision != revision {
+                    cache.update(memory, chunk, revision);
+                }
+                // Only the part of the chunk that fits in the area is drawn.
+                let (chunk_left, chunk_top) = (chunk_x * size, chunk_y * size + size - 1);
+                let (first_x, last_x) = (left.max(chunk_left), right.min(chunk_left + size - 1));
+                let (first_y, last_y) = (bottom.max(chunk_top - size + 1), top.min(chunk_top));
+                let (columns, rows) = (last_x - first_x + 1, last_y - first_y + 1);
+                let (screen_x, screen_y) = to_screen((first_x, last_y));
+                draw_texture_ex(
+                    &cache.texture,
+                    screen_x,
+                    screen_y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(columns as f32 * tile, rows as f32 * tile)),
+                        source: Some(Rect::new(
+                            (first_x - chunk_left) as f32,
+                            (chunk_top - last_y) as f32,
+                            columns as f32,
+                            rows as f32,
+                        )),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        let (screen_x, screen_y) = to_screen(player);
+        if area.contains(vec2(screen_x, screen_y)) {
+            draw_circle(
+                screen_x + tile / 2.,
+                screen_y + tile / 2.,
+                tile.max(3.),
+                YELLOW,
+            );
+        }
+    }
+}
+
+/// The walls and the doors of the huts stand out on the map.
+fn structure_color(remembered: &Remembered) -> Option<Color> {
+    remembered.entities.iter().find_map(|entity| match entity {
+        TileType::WoodenWall | TileType::StoneWall | TileType::WallTorch => {
+            Some(Color::new(0.55, 0.35, 0.2, 1.))
+        }
+        TileType::Door | TileType::OpenDoor | TileType::LockedDoor => {
+            Some(Color::new(0.9, 0.6, 0.2, 1.))
+        }
+        _ => None,
+    })
+}
+
+/// The color of the terrain on the map.
+const fn terrain_color(terrain: TileType) -> Color {
+    match terrain {
+        TileType::GrassFloor | TileType::GrassStones => Color::new(0.3, 0.55, 0.25, 1.),
+        TileType::SandFloor | TileType::SandStones => Color::new(0.8, 0.75, 0.5, 1.),
+        TileType::StoneFloor => Color::new(0.5, 0.5, 0.5, 1.),
+        TileType::Pond | TileType::Puddle => Color::new(0.2, 0.4, 0.8, 1.),
+        TileType::Lava => Color::new(0.9, 0.3, 0.1, 1.),
+        _ => Color::new(0.35, 0.35, 0.35, 1.),
+    }
+}
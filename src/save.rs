@@ -0,0 +1,180 @@
+use crate::coords::{AbsolutePosition, ChunkPosition, LocalPosition};
+
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+/// Where the game is saved to.
+pub const SAVE_PATH: &str = "savegame.sav";
+
+/// A saved game, stored as `key=value` lines just like the settings.
+/// The same key can appear many times for lists of things.
+#[derive(Debug, Default)]
+pub struct SaveFile {
+    entries: Vec<(String, String)>,
+}
+
+impl SaveFile {
+    /// Adds the entry, keeping the ones with the same key.
+    pub fn push<V: Display>(&mut self, key: &str, value: V) {
+        self.entries.push((key.to_string(), value.to_string()));
+    }
+
+    /// Changes the first value saved with the key, or adds the entry if there is none.
+    pub fn set<V: Display>(&mut self, key: &str, value: V) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|(entry_key, _)| entry_key == key)
+        {
+            Some((_, entry_value)) => *entry_value = value.to_string(),
+            None => self.push(key, value),
+        }
+    }
+
+    /// The first value saved with the key.
+    pub fn get<'a>(&'a self, key: &'a str) -> Option<&'a str> {
+        self.get_all(key).next()
+    }
+
+    /// Every value saved with the key.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The first value saved with the key, parsed.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    pub fn write(&self, path: &str) -> std::io::Result<()> {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect();
+        std::fs::write(path, contents)
+    }
+
+    pub fn read(path: &str) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let entries = contents
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Ok(Self { entries })
+    }
+}
+
+/// Finds the variant of the enum by its `Debug` name.
+pub fn parse_variant<T: Debug + Copy>(variants: &[T], name: &str) -> Option<T> {
+    variants
+        .iter()
+        .find(|variant| format!("{:?}", variant) == name)
+        .copied()
+}
+
+/// Parses the comma separated values.
+pub fn parse_list<T: FromStr>(value: &str) -> Option<Vec<T>> {
+    value
+        .split(',')
+        .map(|part| part.trim().parse().ok())
+        .collect()
+}
+
+/// Joins the fields with the separator, a backslash goes before
+/// every separator and backslash inside the fields.
+pub fn join_escaped<S: AsRef<str>>(fields: &[S], separator: char) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let mut escaped = String::new();
+            for character in field.as_ref().chars() {
+                if character == separator || character == '\\' {
+                    escaped.push('\\');
+                }
+                escaped.push(character);
+            }
+            escaped
+        })
+        .collect();
+    escaped.join(separator.to_string().as_str())
+}
+
+/// Splits the fields joined by `join_escaped`, None if a backslash escapes nothing.
+pub fn split_escaped(value: &str, separator: char) -> Option<Vec<String>> {
+    let mut fields = vec![String::new()];
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        let field = fields.last_mut().expect("There is always a field!");
+        match character {
+            '\\' => field.push(characters.next()?),
+            _ if character == separator => fields.push(String::new()),
+            _ => field.push(character),
+        }
+    }
+    Some(fields)
+}
+
+/// Writes the position as `chunk_x,chunk_y,x,y`.
+pub fn format_position(position: &AbsolutePosition) -> String {
+    format!(
+        "{},{},{},{}",
+        position.chunk.x, position.chunk.y, position.local.x, position.local.y
+    )
+}
+
+/// Reads the position written by `format_position`.
+pub fn parse_position(value: &str) -> Option<AbsolutePosition> {
+    match parse_list::<i32>(value)?.as_slice() {
+        [chunk_x, chunk_y, x, y] => Some(AbsolutePosition {
+            chunk: ChunkPosition::new(*chunk_x, *chunk_y),
+            local: LocalPosition::new(*x as i16, *y as i16),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_round_trip() {
+        let mut file = SaveFile::default();
+        file.push("seed", 42);
+        file.push("entity", "a=b");
+        file.push("entity", "c");
+        file.set("seed", 7);
+        let path = std::env::temp_dir().join("kiriroguelike_save_test.sav");
+        let path = path.to_str().expect("The temp path is valid!");
+        file.write(path).expect("The save is written!");
+        let read = SaveFile::read(path).expect("The save is read!");
+        std::fs::remove_file(path).expect("The save is removed!");
+        assert_eq!(read.parse::<i32>("seed"), Some(7));
+        assert_eq!(read.get_all("entity").collect::<Vec<_>>(), vec!["a=b", "c"]);
+        assert_eq!(read.get("missing"), None);
+    }
+
+    #[test]
+    fn escaped_fields_round_trip() {
+        let fields = ["a|b", "c\\", "", "d"];
+        let joined = join_escaped(&fields, '|');
+        assert_eq!(
+            split_escaped(&joined, '|'),
+            Some(fields.map(String::from).to_vec())
+        );
+        assert_eq!(split_escaped("dangling\\", '|'), None);
+    }
+
+    #[test]
+    fn position_round_trip() {
+        let position = AbsolutePosition::from_absolute_i32((-40, 65));
+        let parsed = parse_position(&format_position(&position)).expect("The position parses!");
+        assert_eq!(parsed.get_absolute_position_i32(), (-40, 65));
+        assert!(parse_position("1,2,3").is_none());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::entities::items::{Item, ItemKind};
+use crate::events::{EventBus, EventListener, GameEvent};
+use crate::save::{parse_variant, SaveFile};
+
+use std::collections::HashMap;
+
+/// The points for the valuables the player found. They are never taken away,
+/// the items the player dropped don't count again when they are picked back up.
+#[derive(Debug, Default)]
+pub struct Score {
+    pub points: i64,
+    /// How many of the items of every kind the player dropped and may pick up again.
+    dropped: HashMap<ItemKind, u32>,
+}
+
+impl Score {
+    /// Writes the score as `score=points` and the dropped items as `score_dropped=Kind:count`.
+    pub fn save(&self, file: &mut SaveFile) {
+        file.push("score", self.points);
+        for (kind, count) in &self.dropped {
+            file.push("score_dropped", format!("{:?}:{}", kind, count));
+        }
+    }
+
+    /// Restores the score written by `save`, returns None if it's missing or broken.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let mut score = Self {
+            points: file.parse("score")?,
+            ..Self::default()
+        };
+        for value in file.get_all("score_dropped") {
+            let (kind, count) = value.split_once(':')?;
+            score
+                .dropped
+                .insert(parse_variant(&ItemKind::ALL, kind)?, count.parse().ok()?);
+        }
+        Some(score)
+    }
+
+    /// Adds the points for the picked up item, except for the ones dropped before.
+    fn pick_up(&mut self, item: &Item) {
+        let dropped = self.dropped.entry(item.kind).or_default();
+        let returned = (*dropped).min(item.count);
+        *dropped -= returned;
+        self.points += item.kind.value() * i64::from(item.count - returned);
+    }
+}
+
+impl EventListener for Score {
+    fn on_event(&mut self, event: &GameEvent, _: &mut EventBus) {
+        match event {
+            GameEvent::ItemPickedUp(item) => self.pick_up(item),
+            GameEvent::ItemDropped(item) => {
+                *self.dropped.entry(item.kind).or_default() += item.count;
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::controls::KeyBindings;
+use crate::save::SaveFile;
+
+use std::fmt::Display;
+
+/// Where the settings are loaded from and saved to.
+pub const SETTINGS_PATH: &str = "Settings.config";
+
+const DEFAULT_SCROLL_SPEED: f32 = 0.03;
+const DEFAULT_ZOOM_SPEED: f32 = 0.97;
+const DEFAULT_KEY_REPEAT_DELAY: f32 = 0.25;
+const DEFAULT_KEY_REPEAT_RATE: f32 = 0.08;
+const DEFAULT_CAMERA_DAMPING: f32 = 8.;
+const DEFAULT_CAMERA_DEADZONE: f32 = 1.5;
+
+/// The player's settings, stored as `key=value` lines.
+/// The lines nobody reads yet are kept as they are.
+#[derive(Debug, Default)]
+pub struct Settings {
+    file: SaveFile,
+    /// The keys for the commands, with the `layout=` and the `key_` lines.
+    pub bindings: KeyBindings,
+}
+
+impl Settings {
+    /// Reads the settings file, the missing settings get the default values.
+    pub fn load() -> Self {
+        let file = SaveFile::read(SETTINGS_PATH).unwrap_or_default();
+        Self {
+            bindings: KeyBindings::load(&file),
+            file,
+        }
+    }
+
+    pub fn write(&mut self) -> std::io::Result<()> {
+        self.bindings.save(&mut self.file);
+        self.file.write(SETTINGS_PATH)
+    }
+
+    pub fn set<V: Display>(&mut self, key: &str, value: V) {
+        self.file.set(key, value);
+    }
+
+    /// How fast the camera pans.
+    pub fn scroll_speed(&self) -> f32 {
+        self.file
+            .parse("scroll_speed")
+            .unwrap_or(DEFAULT_SCROLL_SPEED)
+    }
+
+    /// How much the zoom changes every frame, the closer to 1 the slower.
+    pub fn zoom_speed(&self) -> f32 {
+        self.file.parse("zoom_speed").unwrap_or(DEFAULT_ZOOM_SPEED)
+    }
+
+    /// How many seconds the key is held down before the command repeats.
+    pub fn key_repeat_delay(&self) -> f32 {
+        self.file
+            .parse("key_repeat_delay")
+            .unwrap_or(DEFAULT_KEY_REPEAT_DELAY)
+    }
+
+    /// How many seconds pass between the repeats, and between the steps of the run.
+    pub fn key_repeat_rate(&self) -> f32 {
+        self.file
+            .parse("key_repeat_rate")
+            .unwrap_or(DEFAULT_KEY_REPEAT_RATE)
+    }
+
+    /// How quickly the camera catches up with what it follows, the higher the faster.
+    pub fn camera_damping(&self) -> f32 {
+        self.file
+            .parse("camera_damping")
+            .unwrap_or(DEFAULT_CAMERA_DAMPING)
+    }
+
+    /// How many tiles the player can move from the center before the camera follows.
+    pub fn camera_deadzone(&self) -> f32 {
+        self.file
+            .parse("camera_deadzone")
+            .unwrap_or(DEFAULT_CAMERA_DEADZONE)
+    }
+}
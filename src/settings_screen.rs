@@ -0,0 +1,209 @@
+use macroquad::prelude::*;
+
+use crate::controls::{is_bindable, Command, KeyBindings, Layout};
+use crate::settings::Settings;
+
+const LINE_HEIGHT: f32 = 24.;
+
+/// A setting that can be changed on the screen:
+/// the key, the name shown, how much a key press changes it and the limits.
+struct Adjustable {
+    key: &'static str,
+    name: &'static str,
+    get: fn(&Settings) -> f32,
+    step: f32,
+    min: f32,
+    max: f32,
+}
+
+const ADJUSTABLE: [Adjustable; 6] = [
+    Adjustable {
+        key: "zoom_speed",
+        name: "Zoom speed",
+        get: Settings::zoom_speed,
+        step: 0.01,
+        min: 0.8,
+        max: 0.99,
+    },
+    Adjustable {
+        key: "scroll_speed",
+        name: "Scroll speed",
+        get: Settings::scroll_speed,
+        step: 0.01,
+        min: 0.01,
+        max: 0.2,
+    },
+    Adjustable {
+        key: "key_repeat_delay",
+        name: "Key repeat delay",
+        get: Settings::key_repeat_delay,
+        step: 0.05,
+        min: 0.05,
+        max: 1.,
+    },
+    Adjustable {
+        key: "key_repeat_rate",
+        name: "Key repeat rate",
+        get: Settings::key_repeat_rate,
+        step: 0.01,
+        min: 0.02,
+        max: 0.5,
+    },
+    Adjustable {
+        key: "camera_damping",
+        name: "Camera damping",
+        get: Settings::camera_damping,
+        step: 1.,
+        min: 1.,
+        max: 30.,
+    },
+    Adjustable {
+        key: "camera_deadzone",
+        name: "Camera deadzone",
+        get: Settings::camera_deadzone,
+        step: 0.5,
+        min: 0.,
+        max: 6.,
+    },
+];
+
+/// The screen to change the settings and the keys on,
+/// they are written to the file when it closes.
+/// The rows are the adjustable settings, the keyboard layout and then the commands.
+#[derive(Debug, Default)]
+pub struct SettingsScreen {
+    selected: usize,
+    /// Is the next key pressed going to be bound to the selected command?
+    waiting_for_key: bool,
+}
+
+impl SettingsScreen {
+    /// Changes the selected setting with the west and east keys, binds the keys to the commands.
+    /// Returns true when the screen is closed.
+    pub fn handle_input(&mut self, settings: &mut Settings) -> bool {
+        let layout_row = ADJUSTABLE.len();
+        let rows = layout_row + 1 + Command::ALL.len();
+        let command = self
+            .selected
+            .checked_sub(layout_row + 1)
+            .map(|index| Command::ALL[index]);
+
+        if self.waiting_for_key {
+            if settings.bindings.pressed(Command::Cancel) {
+                self.waiting_for_key = false;
+            } else if let Some(key) = get_last_key_pressed().filter(|key| is_bindable(*key)) {
+                settings
+                    .bindings
+                    .bind(command.expect("Only the commands get the keys!"), key);
+                self.waiting_for_key = false;
+            }
+            return false;
+        }
+
+        let bindings = &settings.bindings;
+        if bindings.pressed(Command::MoveSouth) {
+            self.selected = (self.selected + 1) % rows;
+        }
+        if bindings.pressed(Command::MoveNorth) {
+            self.selected = (self.selected + rows - 1) % rows;
+        }
+        let closed = bindings.pressed(Command::Cancel);
+        let (confirmed, cleared) = (
+            bindings.pressed(Command::Confirm),
+            bindings.pressed(Command::Clear),
+        );
+        let direction = if bindings.pressed(Command::MoveEast) {
+            1
+        } else if bindings.pressed(Command::MoveWest) {
+            -1
+        } else {
+            0
+        };
+
+        if let Some(setting) = ADJUSTABLE.get(self.selected) {
+            if direction != 0 {
+                let value = (setting.get)(settings) + setting.step * direction as f32;
+                // Rounded so the file doesn't fill up with the float noise.
+                let value = (value.clamp(setting.min, setting.max) * 100.).round() / 100.;
+                settings.set(setting.key, value);
+            }
+        } else if self.selected == layout_row {
+            // Switching the layout puts back all of its default keys.
+            if direction != 0 {
+                let index = Layout::ALL
+                    .iter()
+                    .position(|layout| *layout == settings.bindings.layout)
+                    .unwrap_or_default();
+                let next = (index as i32 + direction).rem_euclid(Layout::ALL.len() as i32);
+                settings.bindings = KeyBindings::preset(Layout::ALL[next as usize]);
+            }
+        } else if let Some(command) = command {
+            if confirmed {
+                self.waiting_for_key = true;
+            }
+            if cleared {
+                settings.bindings.clear(command);
+            }
+        }
+        closed
+    }
+
+    pub fn draw(&self, settings: &Settings) {
+        clear_background(Color::new(0.1, 0.1, 0.12, 1.));
+        let x = screen_width() / 2. - 200.;
+        let mut y = 50.;
+        draw_text("Settings", x, y, LINE_HEIGHT * 1.5, WHITE);
+        y += LINE_HEIGHT / 2.;
+
+        let mut rows: Vec<String> = ADJUSTABLE
+            .iter()
+            .map(|setting| format!("{}: < {:.2} >", setting.name, (setting.get)(settings)))
+            .collect();
+        rows.push(format!(
+            "Keyboard layout: < {:?} >",
+            settings.bindings.layout
+        ));
+        for (index, command) in Command::ALL.iter().enumerate() {
+            let keys = if self.waiting_for_key && index + ADJUSTABLE.len() + 1 == self.selected {
+                "press a key...".to_string()
+            } else {
+                let keys: Vec<String> = settings
+                    .bindings
+                    .keys(*command)
+                    .iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect();
+                keys.join(", ")
+            };
+            rows.push(format!("{}: {}", command, keys));
+        }
+        // The rows that don't fit scroll along with the selected one.
+        let visible = ((screen_height() - 130.) / LINE_HEIGHT).max(1.) as usize;
+        let first = (self.selected + 1).saturating_sub(visible);
+        for (index, row) in rows.iter().enumerate().skip(first).take(visible) {
+            y += LINE_HEIGHT;
+            let color = if index == self.selected {
+                YELLOW
+            } else {
+                LIGHTGRAY
+            };
+            draw_text(row.as_str(), x, y, LINE_HEIGHT, color);
+        }
+        let bindings = &settings.bindings;
+        draw_text(
+            format!(
+                "{}/{} change, {} add a key, {} clear keys, {} save and go back",
+                bindings.key_name(Command::MoveWest),
+                bindings.key_name(Command::MoveEast),
+                bindings.key_name(Command::Confirm),
+                bindings.key_name(Command::Clear),
+                bindings.key_name(Command::Cancel)
+            )
+            .as_str(),
+            x,
+            y + LINE_HEIGHT * 1.5,
+            18.,
+            GRAY,
+        );
+    }
+}
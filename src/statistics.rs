@@ -0,0 +1,85 @@
+use crate::events::{EventBus, EventListener, GameEvent};
+use crate::save::{parse_list, SaveFile};
+use crate::tile_types::TileType;
+
+/// The numbers about the player's run, shown when the game is over.
+#[derive(Debug, Default)]
+pub struct Statistics {
+    pub turns: u64,
+    pub steps: u64,
+    pub kills: u64,
+    pub items_picked_up: u64,
+    pub chunks_explored: u64,
+    /// How many chunks away from the start the player got.
+    pub farthest_chunk: u64,
+}
+
+impl Statistics {
+    pub fn save(&self, file: &mut SaveFile) {
+        file.push(
+            "statistics",
+            format!(
+                "{},{},{},{},{},{}",
+                self.turns,
+                self.steps,
+                self.kills,
+                self.items_picked_up,
+                self.chunks_explored,
+                self.farthest_chunk
+            ),
+        );
+    }
+
+    /// Restores the statistics written by `save`, returns None if they're missing or broken.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let values = file.get("statistics").and_then(parse_list::<u64>);
+        match values.as_deref() {
+            Some([turns, steps, kills, items_picked_up, chunks_explored, farthest_chunk]) => {
+                Some(Self {
+                    turns: *turns,
+                    steps: *steps,
+                    kills: *kills,
+                    items_picked_up: *items_picked_up,
+                    chunks_explored: *chunks_explored,
+                    farthest_chunk: *farthest_chunk,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The lines for the game over screen.
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!("Steps taken: {}", self.steps),
+            format!("Creatures killed: {}", self.kills),
+            format!("Items picked up: {}", self.items_picked_up),
+            format!(
+                "Chunks explored: {}, the farthest {} chunks away",
+                self.chunks_explored, self.farthest_chunk
+            ),
+        ]
+    }
+}
+
+impl EventListener for Statistics {
+    fn on_event(&mut self, event: &GameEvent, _: &mut EventBus) {
+        match event {
+            GameEvent::EntityMoved {
+                tile: TileType::Pengu,
+                ..
+            } => self.steps += 1,
+            GameEvent::EntityDied { tile, .. } if *tile != TileType::Pengu => self.kills += 1,
+            GameEvent::ItemPickedUp(item) => self.items_picked_up += u64::from(item.count),
+            GameEvent::ChunkLoaded { chunk, first_visit } => {
+                if *first_visit {
+                    self.chunks_explored += 1;
+                }
+                let away = chunk.x.unsigned_abs().max(chunk.y.unsigned_abs());
+                self.farthest_chunk = self.farthest_chunk.max(u64::from(away));
+            }
+            GameEvent::TurnEnded(turn) => self.turns = *turn,
+            _ => {}
+        }
+    }
+}
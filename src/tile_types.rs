@@ -24,14 +24,104 @@ pub enum TileType {
     VioletFlower,
     MushroomRed,
     Placeholder,
+    Rat,
+    Slime,
+    Bag,
+    OpenDoor,
+    LockedDoor,
+    WallTorch,
+    Lava,
+    Ghost,
+    MoonFlower,
+    Puddle,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for TileType {
     fn default() -> Self {
         Self::Debug
     }
 }
 
+impl TileType {
+    /// Every tile type, to find them by name when loading the game.
+    pub const ALL: [Self; 33] = [
+        Self::Debug,
+        Self::WoodenWall,
+        Self::GrassFloor,
+        Self::Pengu,
+        Self::Door,
+        Self::Chest,
+        Self::Coin,
+        Self::Cat,
+        Self::StoneFloor,
+        Self::Bush,
+        Self::GrassStones,
+        Self::Pond,
+        Self::SandFloor,
+        Self::StoneWall,
+        Self::StoneEngraving,
+        Self::SandStones,
+        Self::WhiteFlower,
+        Self::MushroomOrange,
+        Self::MushroomBrown,
+        Self::TreeStomp,
+        Self::VioletFlower,
+        Self::MushroomRed,
+        Self::Placeholder,
+        Self::Rat,
+        Self::Slime,
+        Self::Bag,
+        Self::OpenDoor,
+        Self::LockedDoor,
+        Self::WallTorch,
+        Self::Lava,
+        Self::Ghost,
+        Self::MoonFlower,
+        Self::Puddle,
+    ];
+}
+
+impl TileType {
+    /// A short description of what the tile is, shown when it's examined.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Debug | Self::Placeholder => "Something that shouldn't be here.",
+            Self::WoodenWall => "A wall of rough planks.",
+            Self::GrassFloor => "Soft grass.",
+            Self::Pengu => "A brave little penguin.",
+            Self::Door => "A closed wooden door.",
+            Self::Chest => "A chest, there might be something inside.",
+            Self::Coin => "A shiny coin.",
+            Self::Cat => "A cat, it looks like it wants to talk.",
+            Self::StoneFloor => "Cold stone tiles.",
+            Self::Bush => "A thick bush.",
+            Self::GrassStones => "Pebbles in the grass.",
+            Self::Pond => "A small pond of still water.",
+            Self::SandFloor => "Warm sand.",
+            Self::StoneWall => "A solid stone wall.",
+            Self::StoneEngraving => "A stone with words carved into it.",
+            Self::SandStones => "Pebbles in the sand.",
+            Self::WhiteFlower => "A white flower.",
+            Self::MushroomOrange => "An orange mushroom.",
+            Self::MushroomBrown => "A brown mushroom, it smells nice.",
+            Self::TreeStomp => "What is left of an old tree.",
+            Self::VioletFlower => "A violet flower.",
+            Self::MushroomRed => "A red mushroom with white spots.",
+            Self::Rat => "A hungry rat.",
+            Self::Slime => "A wobbly blob of slime.",
+            Self::Bag => "Something someone left behind.",
+            Self::OpenDoor => "An open door.",
+            Self::LockedDoor => "A locked door, it needs a key.",
+            Self::WallTorch => "A torch burning on the wall.",
+            Self::Lava => "Molten rock, don't step in it.",
+            Self::Ghost => "A restless ghost.",
+            Self::MoonFlower => "A flower that glows in the moonlight.",
+            Self::Puddle => "A puddle of rain water.",
+        }
+    }
+}
+
 impl std::fmt::Display for TileType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
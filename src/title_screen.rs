@@ -0,0 +1,104 @@
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+
+use crate::controls::{Command, KeyBindings};
+use crate::menu::Menu;
+
+/// The longest seed that can be typed in.
+const MAX_SEED_LENGTH: usize = 9;
+
+/// What the player picked on the title screen.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TitleChoice {
+    NewGame(i32),
+    Continue,
+    Settings,
+    Quit,
+}
+
+/// The menu shown before the game starts.
+#[derive(Debug)]
+pub struct TitleScreen {
+    menu: Menu,
+    /// The seed being typed in for the new game.
+    seed: Option<String>,
+    /// Why the last choice didn't work out.
+    pub notice: Option<String>,
+}
+
+impl Default for TitleScreen {
+    fn default() -> Self {
+        Self {
+            menu: Menu::new(vec!["New game", "Continue", "Settings", "Quit"]),
+            seed: None,
+            notice: None,
+        }
+    }
+}
+
+impl TitleScreen {
+    pub fn handle_input(&mut self, bindings: &KeyBindings) -> Option<TitleChoice> {
+        if let Some(seed) = self.seed.as_mut() {
+            let choice = Self::enter_seed(seed, bindings);
+            if choice.is_some() || bindings.pressed(Command::Cancel) {
+                self.seed = None;
+            }
+            return choice;
+        }
+        match self.menu.handle_input(bindings)? {
+            0 => {
+                // Whatever was typed during the last game shouldn't end up in the seed.
+                clear_input_queue();
+                self.seed = Some(String::new());
+                self.notice = None;
+                None
+            }
+            1 => Some(TitleChoice::Continue),
+            2 => Some(TitleChoice::Settings),
+            _ => Some(TitleChoice::Quit),
+        }
+    }
+
+    /// Types the digits of the seed, an empty seed is picked from the clock.
+    fn enter_seed(seed: &mut String, bindings: &KeyBindings) -> Option<TitleChoice> {
+        while let Some(character) = get_char_pressed() {
+            let allowed = character.is_ascii_digit() || (character == '-' && seed.is_empty());
+            if allowed && seed.len() < MAX_SEED_LENGTH {
+                seed.push(character);
+            }
+        }
+        if bindings.pressed(Command::Clear) {
+            seed.pop();
+        }
+        if !bindings.pressed(Command::Confirm) {
+            return None;
+        }
+        let seed = seed
+            .parse()
+            .unwrap_or_else(|_| (date::now() * 1000.) as i64 as i32);
+        Some(TitleChoice::NewGame(seed))
+    }
+
+    pub fn draw(&self, bindings: &KeyBindings) {
+        clear_background(Color::new(0.1, 0.1, 0.12, 1.));
+        self.menu.draw("kiriRoguelike");
+        let (x, y) = (screen_width() / 2. - 160., screen_height() / 3. + 240.);
+        if let Some(seed) = &self.seed {
+            draw_text(format!("Seed: {}_", seed).as_str(), x, y, 32., WHITE);
+            draw_text(
+                format!(
+                    "{} to start, empty for a random seed, {} to go back",
+                    bindings.key_name(Command::Confirm),
+                    bindings.key_name(Command::Cancel)
+                )
+                .as_str(),
+                x,
+                y + 30.,
+                20.,
+                GRAY,
+            );
+        } else if let Some(notice) = &self.notice {
+            draw_text(notice, x, y, 24., Color::new(0.9, 0.4, 0.3, 1.));
+        }
+    }
+}
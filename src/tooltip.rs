@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+
+use crate::coords::AbsolutePosition;
+use crate::entities::entities::Entities;
+use crate::graphics::lighting::LightMap;
+use crate::world::World;
+
+const FONT_SIZE: f32 = 20.;
+const LINE_HEIGHT: f32 = 22.;
+const PADDING: f32 = 8.;
+
+/// Can the player see the tile? It has to be in view and lit, like for the drawing.
+pub fn is_seen(entities: &Entities, light_map: &LightMap, position: (i32, i32)) -> bool {
+    entities.fov.is_visible(position) && light_map.is_lit(position)
+}
+
+/// Describes the terrain and everything on a tile the player can see.
+pub fn describe_tile(
+    world: &World,
+    entities: &Entities,
+    light_map: &LightMap,
+    position: (i32, i32),
+) -> Vec<String> {
+    if !is_seen(entities, light_map, position) {
+        return vec!["It's too dark to see here.".to_string()];
+    }
+    let mut lines = Vec::new();
+    if let Some(terrain) = world.get_tile(&AbsolutePosition::from_absolute_i32(position)) {
+        lines.push(format!("{}: {}", terrain, terrain.description()));
+    }
+    let player = &entities.player.entity;
+    let on_tile = entities.entities_at(position).chain(
+        (player.get_absolute_position().get_absolute_position_i32() == position).then_some(player),
+    );
+    for entity in on_tile {
+        lines.push(format!("{}: {}", entity.tile, entity.tile.description()));
+        let properties = entity.properties();
+        if !properties.is_empty() {
+            lines.push(format!("  {}", properties.join(", ")));
+        }
+    }
+    lines
+}
+
+/// Draws the lines in a box next to the mouse, kept inside the screen.
+pub fn draw_tooltip(lines: &[String]) {
+    let width = lines
+        .iter()
+        .map(|line| measure_text(line, None, FONT_SIZE as u16, 1.).width)
+        .fold(0., f32::max)
+        + PADDING * 2.;
+    let height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.;
+    let (mouse_x, mouse_y) = mouse_position();
+    let x = (mouse_x + 16.).min(screen_width() - width).max(0.);
+    let y = (mouse_y + 16.).min(screen_height() - height).max(0.);
+    draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.8));
+    draw_rectangle_lines(x, y, width, height, 2., GRAY);
+    for (index, line) in lines.iter().enumerate() {
+        draw_text(
+            line.as_str(),
+            x + PADDING,
+            y + PADDING + (index as f32 + 0.8) * LINE_HEIGHT,
+            FONT_SIZE,
+            WHITE,
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use macroquad::prelude::*;
+
+use crate::coords::ChunkPosition;
+use crate::graphics::lighting::LightColor;
+use crate::rng::Rng;
+
+/// How many turns the weather in a region stays the same.
+const WEATHER_PERIOD: u64 = 150;
+/// How many chunks wide a region with the same weather is.
+const REGION_SIZE: i32 = 4;
+/// The percent of the regions that are dry, their storms carry sand instead of rain.
+const DRY_REGIONS: i32 = 30;
+/// How much the weather strengthens or weakens every turn.
+const INTENSITY_STEP: f32 = 0.05;
+/// How far the player sees in the thickest fog.
+const FOG_SIGHT: f32 = 4.;
+const PARTICLES: usize = 300;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Fog,
+    Sandstorm,
+}
+
+impl WeatherKind {
+    pub const ALL: [Self; 4] = [Self::Clear, Self::Rain, Self::Fog, Self::Sandstorm];
+}
+
+impl std::fmt::Display for WeatherKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The weather where the player is.
+/// What weather a region has is decided by the seed and the time,
+/// changes fade in and out over a few turns.
+#[derive(Debug, Clone)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    /// How strong the weather is, from 0 to 1.
+    pub intensity: f32,
+    /// Screen space positions of the rain drops or the sand grains.
+    particles: Vec<Vec2>,
+}
+
+impl Weather {
+    pub fn new(kind: WeatherKind, intensity: f32) -> Self {
+        Self {
+            kind,
+            intensity,
+            particles: Vec::new(),
+        }
+    }
+
+    /// The weather the region of the chunk has at the `turn`.
+    /// The region's own seed decides whether it's dry, the storms there are sandstorms.
+    pub fn forecast(seed: i32, turn: u64, chunk: ChunkPosition) -> WeatherKind {
+        let region = (
+            chunk.x.div_euclid(REGION_SIZE),
+            chunk.y.div_euclid(REGION_SIZE),
+        );
+        let region_seed = (seed as u64)
+            ^ (region.0 as u64).wrapping_mul(0x9E37_79B9)
+            ^ (region.1 as u64).wrapping_mul(0x85EB_CA6B);
+        let dry = Rng::new(region_seed).range(0, 100) < DRY_REGIONS;
+        let period = turn / WEATHER_PERIOD;
+        match Rng::new(region_seed ^ period.wrapping_mul(0xC2B2_AE35)).range(0, 100) {
+            0..=54 => WeatherKind::Clear,
+            55..=79 if dry => WeatherKind::Sandstorm,
+            55..=79 => WeatherKind::Rain,
+            _ => WeatherKind::Fog,
+        }
+    }
+
+    /// Moves the weather one turn closer to the `target`,
+    /// the old weather has to calm down before the new one starts.
+    pub fn update(&mut self, target: WeatherKind) {
+        if self.kind == target {
+            self.intensity = (self.intensity + INTENSITY_STEP).min(1.);
+        } else {
+            self.intensity -= INTENSITY_STEP;
+            if self.intensity <= 0. {
+                self.kind = target;
+                self.intensity = 0.;
+            }
+        }
+    }
+
+    pub fn is_raining(&self) -> bool {
+        self.kind == WeatherKind::Rain && self.intensity > 0.5
+    }
+
+    /// How far the player sees through the weather, at most `sight`.
+    pub fn limit_sight(&self, sight: i16) -> i16 {
+        let limit = match self.kind {
+            WeatherKind::Fog => FOG_SIGHT,
+            WeatherKind::Sandstorm => FOG_SIGHT * 2.,
+            WeatherKind::Clear | WeatherKind::Rain => return sight,
+        };
+        let sight = f32::from(sight);
+        (sight - (sight - limit).max(0.) * self.intensity) as i16
+    }
+
+    /// Clouds, fog and sand dim and tint the light.
+    pub fn filter_light(&self, light: LightColor) -> LightColor {
+        let (r, g, b) = match self.kind {
+            WeatherKind::Clear => (1., 1., 1.),
+            WeatherKind::Rain => (0.65, 0.7, 0.8),
+            WeatherKind::Fog => (0.75, 0.75, 0.75),
+            WeatherKind::Sandstorm => (0.9, 0.7, 0.45),
+        };
+        let mix = |channel: f32, filter: f32| channel * (1. - (1. - filter) * self.intensity);
+        LightColor::new(mix(light.r, r), mix(light.g, g), mix(light.b, b))
+    }
+
+    /// Draws the weather over the whole screen, moving the particles with the frame time.
+    pub fn draw(&mut self) {
+        let (width, height) = (screen_width(), screen_height());
+        if self.particles.len() != PARTICLES {
+            self.particles = (0..PARTICLES)
+                .map(|_| vec2(rand::gen_range(0., width), rand::gen_range(0., height)))
+                .collect();
+        }
+        let visible = (PARTICLES as f32 * self.intensity) as usize;
+        let delta = get_frame_time();
+        match self.kind {
+            WeatherKind::Clear => {}
+            WeatherKind::Rain => {
+                let velocity = vec2(-120., 700.);
+                let color = Color::new(0.6, 0.7, 0.9, 0.5);
+                for drop in self.particles.iter_mut().take(visible) {
+                    *drop += velocity * delta;
+                    wrap(drop, width, height);
+                    let tail = *drop - velocity * 0.02;
+                    draw_line(drop.x, drop.y, tail.x, tail.y, 1., color);
+                }
+            }
+            WeatherKind::Fog => {
+                let color = Color::new(0.7, 0.7, 0.75, 0.25 * self.intensity);
+                draw_rectangle(0., 0., width, height, color);
+                let drift = vec2(15., 0.);
+                for (index, patch) in self.particles.iter_mut().take(visible / 10).enumerate() {
+                    *patch += drift * delta;
+                    wrap(patch, width, height);
+                    let radius = 60. + (index % 5) as f32 * 20.;
+                    draw_circle(patch.x, patch.y, radius, Color::new(0.8, 0.8, 0.85, 0.05));
+                }
+            }
+            WeatherKind::Sandstorm => {
+                let color = Color::new(0.8, 0.6, 0.3, 0.2 * self.intensity);
+                draw_rectangle(0., 0., width, height, color);
+                let velocity = vec2(600., 60.);
+                for grain in self.particles.iter_mut().take(visible) {
+                    *grain += velocity * delta;
+                    wrap(grain, width, height);
+                    draw_line(
+                        grain.x,
+                        grain.y,
+                        grain.x - 8.,
+                        grain.y - 1.,
+                        1.,
+                        Color::new(0.9, 0.75, 0.45, 0.6),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::new(WeatherKind::Clear, 0.)
+    }
+}
+
+/// Brings the particle that left the screen back from the other side.
+fn wrap(particle: &mut Vec2, width: f32, height: f32) {
+    particle.x = particle.x.rem_euclid(width);
+    particle.y = particle.y.rem_euclid(height);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forecast_is_the_same_for_the_whole_period_and_region() {
+        let chunk = ChunkPosition::new(4, -8);
+        let forecast = Weather::forecast(7, WEATHER_PERIOD * 3, chunk);
+        for turn in WEATHER_PERIOD * 3..WEATHER_PERIOD * 4 {
+            for (x, y) in [(4, -8), (7, -5), (5, -6)] {
+                let neighbour = ChunkPosition::new(x, y);
+                assert_eq!(Weather::forecast(7, turn, neighbour), forecast);
+            }
+        }
+    }
+
+    #[test]
+    fn dry_regions_have_no_rain() {
+        for region in 0..50 {
+            let chunk = ChunkPosition::new(region * REGION_SIZE, 0);
+            let kinds: Vec<WeatherKind> = (0..40)
+                .map(|period| Weather::forecast(3, period * WEATHER_PERIOD, chunk))
+                .collect();
+            assert!(
+                !(kinds.contains(&WeatherKind::Rain) && kinds.contains(&WeatherKind::Sandstorm))
+            );
+        }
+    }
+}
@@ -1,16 +1,20 @@
-use crate::coords::{AbsolutePosition, ChunkPosition};
-use crate::entities::player::Player;
+use crate::coords::{AbsolutePosition, ChunkPosition, LocalPosition, CHUNK_SIZE};
+use crate::fov::FieldOfView;
 use crate::generator::Generator;
 use crate::graphics::chunk_terrain::ChunkTerrain;
+use crate::graphics::lighting::{Light, LightMap};
 use crate::graphics::tile_atlas::TileAtlas;
+use crate::save::{parse_variant, SaveFile};
 use crate::tile_types::TileType;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Handles the chunks, terrain.
 pub struct World {
     pub positions_of_chunks_in_view: Vec<ChunkPosition>,
     chunks: HashMap<ChunkPosition, ChunkTerrain>,
+    /// The chunks with the changed terrain, they are saved instead of generated again.
+    dirty_chunks: HashSet<ChunkPosition>,
 }
 
 impl World {
@@ -20,13 +24,92 @@ impl World {
     }
 
     /// Draws every chunk that is in view.
-    pub fn draw(&self, tile_atlas: &TileAtlas, player: &Player) {
+    pub fn draw(&self, tile_atlas: &TileAtlas, fov: &FieldOfView, light_map: &LightMap) {
         let chunks = self.get_visible_chunks();
         for chunk in chunks {
-            tile_atlas.draw_layer(chunk, player);
+            tile_atlas.draw_layer(chunk, fov, light_map);
         }
     }
 
+    /// Can something stand on the tile at `AbsolutePosition`?
+    /// Lava and chunks that aren't generated yet can't be walked on.
+    pub fn is_walkable(&self, position: &AbsolutePosition) -> bool {
+        matches!(self.get_tile(position), Some(tile) if *tile != TileType::Lava)
+    }
+
+    /// All the glowing terrain tiles in view.
+    pub fn light_sources(&self) -> Vec<((i32, i32), Light)> {
+        let mut lights = Vec::new();
+        for chunk in self.get_visible_chunks() {
+            for (tile_type, position) in chunk {
+                if let Some(light) = Light::emitted_by(tile_type) {
+                    lights.push((position.get_absolute_position_i32(), light));
+                }
+            }
+        }
+        lights
+    }
+
+    /// Changes the terrain tile at `AbsolutePosition` and marks the chunk as changed.
+    /// Returns whether the chunk is there to be changed.
+    pub fn set_tile(&mut self, position: &AbsolutePosition, tile: TileType) -> bool {
+        match self.chunks.get_mut(&position.chunk) {
+            Some(chunk) => {
+                chunk.set_tile(&position.local, tile);
+                self.dirty_chunks.insert(position.chunk);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Writes down the chunks with the changed terrain as `chunk_x,chunk_y,tiles...`.
+    pub fn save(&self, file: &mut SaveFile) {
+        for chunk_pos in &self.dirty_chunks {
+            let chunk = &self.chunks[chunk_pos];
+            let tiles: Vec<String> = chunk
+                .into_iter()
+                .map(|(tile, _)| format!("{:?}", tile))
+                .collect();
+            file.push(
+                "chunk",
+                format!("{},{},{}", chunk_pos.x, chunk_pos.y, tiles.join(",")),
+            );
+        }
+    }
+
+    /// Restores the changed chunks written down by `save`,
+    /// the rest of the world is generated again.
+    pub fn load(file: &SaveFile) -> Option<Self> {
+        let mut world = Self::default();
+        for value in file.get_all("chunk") {
+            let mut parts = value.split(',');
+            let chunk_pos =
+                ChunkPosition::new(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+            let tiles = parts
+                .map(|name| parse_variant(&TileType::ALL, name))
+                .collect::<Option<Vec<TileType>>>()?;
+            if tiles.len() != usize::from(CHUNK_SIZE) * usize::from(CHUNK_SIZE) {
+                return None;
+            }
+            let size = usize::from(CHUNK_SIZE);
+            let tile_types = tiles.chunks(size).map(<[TileType]>::to_vec).collect();
+            let positions = (0..CHUNK_SIZE)
+                .map(|y| {
+                    (0..CHUNK_SIZE)
+                        .map(|x| LocalPosition::new(x as i16, y as i16))
+                        .collect()
+                })
+                .collect();
+            world.chunks.insert(
+                chunk_pos,
+                ChunkTerrain::new(chunk_pos, tile_types, positions),
+            );
+            world.dirty_chunks.insert(chunk_pos);
+        }
+        Some(world)
+    }
+
     /// Generates the chunk at `ChunkPosition` and adds it to the world.
     fn gen_chunk(&mut self, chunk_pos: ChunkPosition, generator: &Generator) {
         self.positions_of_chunks_in_view.push(chunk_pos);
@@ -99,6 +182,7 @@ impl Default for World {
         Self {
             positions_of_chunks_in_view,
             chunks,
+            dirty_chunks: HashSet::new(),
         }
     }
 }